@@ -0,0 +1,272 @@
+//! Cache of finished translations so repeated input doesn't hit the API again.
+//!
+//! Entries live in an in-memory LRU and, when enabled in the settings, are
+//! mirrored to `translation_cache.json` in the app config dir so they survive
+//! restarts. Inserts are batched into one write a few seconds later, plus a
+//! final flush on exit.
+
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use log::{info, warn};
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::engines::{Translation, TranslationEngine, TranslationRequest};
+use crate::error::AppError;
+use crate::glossary;
+use crate::AppSettings;
+
+const CACHE_CAPACITY: usize = 500;
+const CACHE_FILE: &str = "translation_cache.json";
+/// How long inserts are collected before the cache file is rewritten
+const WRITE_DELAY: Duration = Duration::from_secs(5);
+
+/// Everything that can change the output of a translation call
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CacheKey {
+    engine: String,
+    model: Option<String>,
+    /// See [`TranslationEngine::output_config`]
+    #[serde(default)]
+    config: Vec<String>,
+    text: String,
+    #[serde(default)]
+    source_lang: Option<String>,
+    target_lang: String,
+    tone: String,
+    /// Glossary terms that apply, so editing the glossary invalidates entries
+    #[serde(default)]
+    glossary: Vec<(String, String)>,
+}
+
+impl CacheKey {
+    pub fn new(
+        engine: &dyn TranslationEngine,
+        settings: &AppSettings,
+        request: &TranslationRequest,
+    ) -> Self {
+        // Machine engines ignore the tone, so don't let it split their entries
+        let tone = if engine.capabilities().supports_tone {
+            request.tone.clone()
+        } else {
+            String::new()
+        };
+
+        Self {
+            engine: engine.id().to_string(),
+            model: engine.model(settings).map(str::to_string),
+            config: engine.output_config(settings),
+            text: normalize_text(&request.text),
+            source_lang: request.source_lang.as_deref().map(str::to_ascii_lowercase),
+            target_lang: request.target_lang.to_ascii_lowercase(),
+            tone,
+            glossary: glossary::matching_terms(&settings.glossary, request)
+                .into_iter()
+                .map(|term| (term.source_term.clone(), term.target_term.clone()))
+                .collect(),
+        }
+    }
+}
+
+/// Trim the text and collapse whitespace runs within each line
+fn normalize_text(text: &str) -> String {
+    text.trim()
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    key: CacheKey,
+    translation: Translation,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheInfo {
+    pub entries: usize,
+    pub capacity: usize,
+    pub disk_enabled: bool,
+    /// Size of the cache file, if one has been written
+    pub disk_bytes: Option<u64>,
+}
+
+struct CacheState {
+    entries: LruCache<CacheKey, Translation>,
+    /// Whether the cache file has been merged into `entries` yet
+    disk_loaded: bool,
+    /// Whether entries changed since the cache file was last written
+    dirty: bool,
+    /// Whether a delayed write is already scheduled
+    write_scheduled: bool,
+}
+
+pub struct TranslationCache(Mutex<CacheState>);
+
+impl Default for TranslationCache {
+    fn default() -> Self {
+        let capacity = NonZeroUsize::new(CACHE_CAPACITY).unwrap();
+        Self(Mutex::new(CacheState {
+            entries: LruCache::new(capacity),
+            disk_loaded: false,
+            dirty: false,
+            write_scheduled: false,
+        }))
+    }
+}
+
+fn cache_file_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| AppError::storage(format!("Failed to resolve app config dir: {}", e)))?;
+    Ok(dir.join(CACHE_FILE))
+}
+
+impl TranslationCache {
+    pub async fn get(
+        &self,
+        app: &AppHandle,
+        disk_cache_enabled: bool,
+        key: &CacheKey,
+    ) -> Option<Translation> {
+        if disk_cache_enabled {
+            self.load_from_disk(app).await;
+        }
+        self.0.lock().unwrap().entries.get(key).cloned()
+    }
+
+    /// Add an entry; with the disk cache on, the file is written
+    /// [`WRITE_DELAY`] later together with any other inserts made meanwhile
+    pub fn insert(
+        &self,
+        app: &AppHandle,
+        disk_cache_enabled: bool,
+        key: CacheKey,
+        translation: Translation,
+    ) {
+        let schedule_write = {
+            let mut state = self.0.lock().unwrap();
+            state.entries.put(key, translation);
+            if !disk_cache_enabled {
+                return;
+            }
+            state.dirty = true;
+            !std::mem::replace(&mut state.write_scheduled, true)
+        };
+
+        if schedule_write {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep(WRITE_DELAY).await;
+                let cache = app.state::<TranslationCache>();
+                cache.0.lock().unwrap().write_scheduled = false;
+                cache.flush(&app).await;
+            });
+        }
+    }
+
+    /// Write pending inserts to the cache file now
+    pub async fn flush(&self, app: &AppHandle) {
+        if !std::mem::take(&mut self.0.lock().unwrap().dirty) {
+            return;
+        }
+        if let Err(e) = self.write_to_disk(app).await {
+            warn!("[cache] {}", e);
+        }
+    }
+
+    pub async fn info(&self, app: &AppHandle, disk_cache_enabled: bool) -> CacheInfo {
+        let disk_bytes = match cache_file_path(app) {
+            Ok(path) => tokio::fs::metadata(path).await.ok().map(|m| m.len()),
+            Err(_) => None,
+        };
+        let state = self.0.lock().unwrap();
+        CacheInfo {
+            entries: state.entries.len(),
+            capacity: state.entries.cap().get(),
+            disk_enabled: disk_cache_enabled,
+            disk_bytes,
+        }
+    }
+
+    /// Drop every entry and delete the cache file
+    pub async fn clear(&self, app: &AppHandle) -> Result<(), AppError> {
+        {
+            let mut state = self.0.lock().unwrap();
+            state.entries.clear();
+            state.dirty = false;
+        }
+        let path = cache_file_path(app)?;
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(AppError::storage(format!(
+                "Failed to remove cache file: {}",
+                e
+            ))),
+        }
+    }
+
+    async fn load_from_disk(&self, app: &AppHandle) {
+        if self.0.lock().unwrap().disk_loaded {
+            return;
+        }
+
+        let entries = match cache_file_path(app) {
+            Ok(path) => match tokio::fs::read_to_string(&path).await {
+                Ok(data) => serde_json::from_str::<Vec<CacheEntry>>(&data).unwrap_or_else(|e| {
+                    warn!("[cache] Ignoring unreadable cache file: {}", e);
+                    Vec::new()
+                }),
+                Err(_) => Vec::new(),
+            },
+            Err(_) => Vec::new(),
+        };
+
+        let mut state = self.0.lock().unwrap();
+        if state.disk_loaded {
+            return;
+        }
+        state.disk_loaded = true;
+        info!("[cache] Loaded {} entries from disk", entries.len());
+        // The file is stored oldest first, so pushing in order keeps the LRU order
+        for entry in entries {
+            if !state.entries.contains(&entry.key) {
+                state.entries.push(entry.key, entry.translation);
+            }
+        }
+    }
+
+    async fn write_to_disk(&self, app: &AppHandle) -> Result<(), AppError> {
+        let path = cache_file_path(app)?;
+        let data = {
+            let state = self.0.lock().unwrap();
+            let entries: Vec<CacheEntry> = state
+                .entries
+                .iter()
+                .rev()
+                .map(|(key, translation)| CacheEntry {
+                    key: key.clone(),
+                    translation: translation.clone(),
+                })
+                .collect();
+            serde_json::to_string(&entries)
+                .map_err(|e| AppError::storage(format!("Failed to serialize cache: {}", e)))?
+        };
+
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir)
+                .await
+                .map_err(|e| AppError::storage(format!("Failed to create cache dir: {}", e)))?;
+        }
+        tokio::fs::write(&path, data)
+            .await
+            .map_err(|e| AppError::storage(format!("Failed to write cache: {}", e)))
+    }
+}
@@ -0,0 +1,163 @@
+//! Source language detection, used by `detect_language` and the auto
+//! direction mode of the translation commands.
+
+use log::warn;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use whatlang::Lang;
+
+use crate::engines::tencent;
+use crate::error::{AppError, ErrorCode};
+use crate::languages::{self, same_language};
+use crate::AppSettings;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LanguageDetector {
+    /// Bundled statistical detector, no network access
+    #[default]
+    Offline,
+    /// Tencent TMT LanguageDetect, falls back to offline detection on errors
+    Tencent,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DetectedLanguage {
+    /// Registry code where one exists, e.g. `zh-Hant` rather than Tencent's `zh-TW`
+    pub lang: String,
+    /// Between 0 and 1; only reported by the offline detector
+    pub confidence: Option<f64>,
+    pub detector: LanguageDetector,
+}
+
+/// ISO 639-1 code for a whatlang language, or its 639-3 code otherwise
+fn iso_639_1(lang: Lang) -> &'static str {
+    match lang {
+        Lang::Cmn => "zh",
+        Lang::Eng => "en",
+        Lang::Jpn => "ja",
+        Lang::Kor => "ko",
+        Lang::Fra => "fr",
+        Lang::Deu => "de",
+        Lang::Spa => "es",
+        Lang::Por => "pt",
+        Lang::Ita => "it",
+        Lang::Rus => "ru",
+        Lang::Ukr => "uk",
+        Lang::Ara => "ar",
+        Lang::Hin => "hi",
+        Lang::Tha => "th",
+        Lang::Vie => "vi",
+        Lang::Ind => "id",
+        Lang::Tur => "tr",
+        Lang::Nld => "nl",
+        Lang::Pol => "pl",
+        Lang::Swe => "sv",
+        other => other.code(),
+    }
+}
+
+/// Frequent characters written differently in Traditional and Simplified
+/// Chinese, pairwise in the same order
+const TRADITIONAL_CHARS: &str =
+    "這們個來說為對時會國過學還麼樣從點開關發問書體長見電話語東車門實現經當";
+const SIMPLIFIED_CHARS: &str =
+    "这们个来说为对时会国过学还么样从点开关发问书体长见电话语东车门实现经当";
+
+/// whatlang reports both scripts as Mandarin, so tell them apart by the
+/// characters that differ
+fn chinese_code(text: &str) -> &'static str {
+    let count = |chars: &str| text.chars().filter(|c| chars.contains(*c)).count();
+    if count(TRADITIONAL_CHARS) > count(SIMPLIFIED_CHARS) {
+        "zh-Hant"
+    } else {
+        "zh"
+    }
+}
+
+/// Map a detector's code onto the language registry, keeping unknown codes
+fn registry_code(lang: &str) -> String {
+    languages::find(lang).map_or_else(|| lang.to_string(), |language| language.code.to_string())
+}
+
+/// `None` when the text is too short or mixed for a reliable guess
+pub fn detect_offline(text: &str) -> Option<DetectedLanguage> {
+    let info = whatlang::detect(text).filter(|info| info.is_reliable())?;
+    let code = match info.lang() {
+        Lang::Cmn => chinese_code(text),
+        lang => iso_639_1(lang),
+    };
+    Some(DetectedLanguage {
+        lang: registry_code(code),
+        confidence: Some(info.confidence()),
+        detector: LanguageDetector::Offline,
+    })
+}
+
+/// Detect with the configured detector
+pub async fn detect(
+    client: &Client,
+    settings: &AppSettings,
+    text: &str,
+) -> Result<DetectedLanguage, AppError> {
+    if settings.language_detector == LanguageDetector::Tencent {
+        match tencent::detect_language(client, settings, text).await {
+            Ok(lang) => {
+                return Ok(DetectedLanguage {
+                    lang: registry_code(&lang),
+                    confidence: None,
+                    detector: LanguageDetector::Tencent,
+                })
+            }
+            Err(e) => warn!("[detect] Tencent detection failed, using offline: {}", e),
+        }
+    }
+
+    detect_offline(text)
+        .ok_or_else(|| AppError::new(ErrorCode::UnsupportedInput, "无法识别文本语言"))
+}
+
+/// Auto direction: text that is already in the target language goes to the
+/// secondary language instead, so one hotkey covers both directions.
+pub async fn resolve_target_lang(
+    client: &Client,
+    settings: &AppSettings,
+    text: &str,
+    target_lang: &str,
+) -> Option<String> {
+    let secondary = settings.secondary_lang.as_deref()?;
+    if !settings.auto_direction || same_language(secondary, target_lang) {
+        return None;
+    }
+
+    match detect(client, settings, text).await {
+        Ok(detected) if same_language(&detected.lang, target_lang) => Some(secondary.to_string()),
+        Ok(_) => None,
+        Err(e) => {
+            warn!("[detect] {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offline_detection_tells_chinese_scripts_apart() {
+        let traditional = detect_offline("這個問題我們明天再說，現在先開會討論時間安排。").unwrap();
+        assert_eq!(traditional.lang, "zh-Hant");
+
+        let simplified = detect_offline("这个问题我们明天再说，现在先开会讨论时间安排。").unwrap();
+        assert_eq!(simplified.lang, "zh");
+    }
+
+    #[test]
+    fn traditional_input_is_not_the_simplified_target() {
+        // Auto direction converts it rather than flipping to the secondary language
+        let detected = detect_offline("這個問題我們明天再說，現在先開會討論時間安排。").unwrap();
+        assert!(!same_language(&detected.lang, "zh-Hans"));
+        assert!(same_language(&detected.lang, "zh-TW"));
+    }
+}
@@ -0,0 +1,192 @@
+//! Anthropic Messages API (`/v1/messages`) engine.
+
+use log::info;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use super::prompt::{build_system_prompt, strip_code_fences};
+use super::retry;
+use super::{
+    endpoint_url, EngineCapabilities, EngineFuture, EngineKind, Translation, TranslationEngine,
+    TranslationRequest,
+};
+use crate::error::AppError;
+use crate::AppSettings;
+
+pub const DEFAULT_ANTHROPIC_BASE_URL: &str = "https://api.anthropic.com";
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+#[derive(Debug, Serialize)]
+struct MessagesRequest<'a> {
+    model: &'a str,
+    max_tokens: u32,
+    system: String,
+    messages: Vec<MessagesMessage<'a>>,
+    temperature: f32,
+}
+
+#[derive(Debug, Serialize)]
+struct MessagesMessage<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessagesResponse {
+    content: Vec<ContentBlock>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContentBlock {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessagesErrorResponse {
+    error: MessagesError,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessagesError {
+    #[serde(rename = "type")]
+    kind: String,
+    message: String,
+}
+
+pub struct AnthropicEngine;
+
+impl TranslationEngine for AnthropicEngine {
+    fn id(&self) -> &'static str {
+        "anthropic"
+    }
+
+    fn capabilities(&self) -> EngineCapabilities {
+        EngineCapabilities {
+            id: self.id(),
+            name: "Anthropic Claude",
+            kind: EngineKind::Llm,
+            supports_tone: true,
+            supports_streaming: false,
+        }
+    }
+
+    fn validate_config(&self, settings: &AppSettings) -> Result<(), AppError> {
+        if settings.anthropic_api_key.is_none() {
+            return Err(AppError::invalid_config("未配置 Anthropic API Key"));
+        }
+        if settings.anthropic_model.is_none() {
+            return Err(AppError::invalid_config("未配置 Anthropic 模型名称"));
+        }
+        Ok(())
+    }
+
+    fn model<'a>(&self, settings: &'a AppSettings) -> Option<&'a str> {
+        settings.anthropic_model.as_deref()
+    }
+
+    fn output_config(&self, settings: &AppSettings) -> Vec<String> {
+        settings.anthropic_base_url.iter().cloned().collect()
+    }
+
+    fn translate<'a>(
+        &'a self,
+        client: &'a Client,
+        settings: &'a AppSettings,
+        request: &'a TranslationRequest,
+    ) -> EngineFuture<'a, Translation> {
+        Box::pin(async move {
+            translate_anthropic(client, settings, request)
+                .await
+                .map(Translation::from)
+        })
+    }
+}
+
+async fn translate_anthropic(
+    client: &Client,
+    settings: &AppSettings,
+    request: &TranslationRequest,
+) -> Result<String, AppError> {
+    let api_key = settings
+        .anthropic_api_key
+        .as_deref()
+        .ok_or_else(|| AppError::invalid_config("未配置 Anthropic API Key"))?;
+    let model = settings
+        .anthropic_model
+        .as_deref()
+        .ok_or_else(|| AppError::invalid_config("未配置 Anthropic 模型名称"))?;
+    // Either a base URL (`https://api.anthropic.com`) or the full URL
+    let url = endpoint_url(
+        settings
+            .anthropic_base_url
+            .as_deref()
+            .unwrap_or(DEFAULT_ANTHROPIC_BASE_URL),
+        "/v1/messages",
+    );
+
+    info!(
+        "[anthropic] POST {} model: {}, text length: {}, target: {}, tone: {}",
+        url,
+        model,
+        request.text.len(),
+        request.target_lang,
+        request.tone
+    );
+
+    let payload = MessagesRequest {
+        model,
+        max_tokens: 4096,
+        system: build_system_prompt(&settings.glossary, request),
+        messages: vec![MessagesMessage {
+            role: "user",
+            content: &request.text,
+        }],
+        temperature: 0.2,
+    };
+
+    let request = client
+        .post(&url)
+        .header("x-api-key", api_key)
+        .header("anthropic-version", ANTHROPIC_VERSION)
+        .json(&payload);
+    let res = retry::send(settings, "anthropic", request)
+        .await
+        .map_err(|e| AppError::transport(format!("请求失败: {}", e), &e))?;
+
+    if !res.status().is_success() {
+        let status = res.status();
+        let body = res.text().await.unwrap_or_default();
+        return Err(AppError::from_status(
+            status,
+            match serde_json::from_str::<MessagesErrorResponse>(&body) {
+                Ok(err) => format!(
+                    "Anthropic API Error ({}): {}",
+                    err.error.kind, err.error.message
+                ),
+                Err(_) => format!("接口返回错误 ({}): {}", status, body),
+            },
+        ));
+    }
+
+    let data: MessagesResponse = res
+        .json()
+        .await
+        .map_err(|e| AppError::invalid_response(format!("解析响应失败: {}", e)))?;
+
+    // The reply may be split across several text blocks
+    let content: String = data
+        .content
+        .into_iter()
+        .filter(|block| block.kind == "text")
+        .filter_map(|block| block.text)
+        .collect();
+
+    if content.trim().is_empty() {
+        return Err(AppError::invalid_response("翻译失败：未返回内容"));
+    }
+
+    Ok(strip_code_fences(&content))
+}
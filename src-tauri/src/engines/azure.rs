@@ -0,0 +1,333 @@
+//! Microsoft Azure Translator v3 engine.
+//!
+//! Besides the single-target [`TranslationEngine`] implementation, Azure can
+//! fan one request out to several target languages, see [`translate_multi`].
+
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+
+use super::retry;
+use super::{
+    EngineCapabilities, EngineFuture, EngineKind, Translation, TranslationEngine,
+    TranslationRequest,
+};
+use crate::error::{AppError, ErrorCode};
+use crate::languages::{self, CodeTable, Language};
+use crate::AppSettings;
+
+pub const DEFAULT_AZURE_ENDPOINT: &str = "https://api.cognitive.microsofttranslator.com";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AzureTextType {
+    #[default]
+    Plain,
+    Html,
+}
+
+impl AzureTextType {
+    fn as_str(self) -> &'static str {
+        match self {
+            AzureTextType::Plain => "plain",
+            AzureTextType::Html => "html",
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[allow(non_snake_case)]
+struct AzureRequestItem<'a> {
+    Text: &'a str,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzureResponseItem {
+    detected_language: Option<AzureDetectedLanguage>,
+    translations: Vec<AzureTranslation>,
+}
+
+#[derive(Deserialize)]
+struct AzureDetectedLanguage {
+    language: String,
+}
+
+#[derive(Deserialize)]
+struct AzureTranslation {
+    text: String,
+    to: String,
+}
+
+#[derive(Deserialize)]
+struct AzureErrorResponse {
+    error: AzureError,
+}
+
+#[derive(Deserialize)]
+struct AzureError {
+    code: i64,
+    message: String,
+}
+
+/// One target of a multi-target Azure call
+#[derive(Debug, Clone, Serialize)]
+pub struct AzureTargetTranslation {
+    pub target_lang: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AzureMultiTranslation {
+    pub detected_source_lang: Option<String>,
+    pub translations: Vec<AzureTargetTranslation>,
+}
+
+const LANGUAGE_CODES: CodeTable = &[
+    ("zh", "zh-Hans"),
+    ("zh-Hant", "zh-Hant"),
+    ("en", "en"),
+    ("ja", "ja"),
+    ("ko", "ko"),
+    ("fr", "fr"),
+    ("de", "de"),
+    ("es", "es"),
+    ("pt", "pt"),
+    ("it", "it"),
+    ("ru", "ru"),
+    ("ar", "ar"),
+    ("th", "th"),
+    ("vi", "vi"),
+    ("id", "id"),
+    ("tr", "tr"),
+    ("nl", "nl"),
+    ("pl", "pl"),
+];
+
+fn azure_lang(lang: &str) -> String {
+    languages::engine_code(LANGUAGE_CODES, lang).to_string()
+}
+
+fn azure_endpoint(settings: &AppSettings) -> &str {
+    settings
+        .azure_endpoint
+        .as_deref()
+        .unwrap_or(DEFAULT_AZURE_ENDPOINT)
+        .trim_end_matches('/')
+}
+
+/// Query of a `/translate` call; `to` is repeated for each target language
+fn translate_query(
+    source_lang: Option<&str>,
+    target_langs: &[String],
+    text_type: AzureTextType,
+) -> Vec<(&'static str, String)> {
+    let mut query = vec![
+        ("api-version", "3.0".to_string()),
+        ("textType", text_type.as_str().to_string()),
+    ];
+    if let Some(source) = source_lang {
+        query.push(("from", azure_lang(source)));
+    }
+    for target in target_langs {
+        query.push(("to", azure_lang(target)));
+    }
+    query
+}
+
+/// Classify an error response by the code in its body, falling back to the
+/// HTTP status
+fn azure_error(status: StatusCode, body: &str) -> AppError {
+    match serde_json::from_str::<AzureErrorResponse>(body) {
+        Ok(err) => {
+            let message = format!(
+                "Azure API Error ({}): {}",
+                err.error.code, err.error.message
+            );
+            // 403001: the free tier's character quota is used up
+            if err.error.code == 403001 {
+                AppError::new(ErrorCode::Quota, message)
+            } else {
+                AppError::from_status(status, message)
+            }
+        }
+        Err(_) => AppError::from_status(status, format!("Azure API Error ({}): {}", status, body)),
+    }
+}
+
+/// Translate `text` into every language in `target_langs` with one request.
+/// Without `source_lang` Azure detects the source language.
+pub async fn translate_multi(
+    client: &Client,
+    settings: &AppSettings,
+    text: &str,
+    source_lang: Option<&str>,
+    target_langs: &[String],
+    text_type: AzureTextType,
+) -> Result<AzureMultiTranslation, AppError> {
+    let key = settings
+        .azure_subscription_key
+        .as_deref()
+        .ok_or_else(|| AppError::invalid_config("未配置 Azure Translator 订阅密钥"))?;
+    if target_langs.is_empty() {
+        return Err(AppError::rejected("No target language given"));
+    }
+
+    let endpoint = azure_endpoint(settings);
+    let query = translate_query(source_lang, target_langs, text_type);
+
+    let mut builder = client
+        .post(format!("{}/translate", endpoint))
+        .query(&query)
+        .header("Ocp-Apim-Subscription-Key", key)
+        .json(&[AzureRequestItem { Text: text }]);
+    // Only regional (non-global) resources need the region header
+    if let Some(region) = settings.azure_region.as_deref() {
+        builder = builder.header("Ocp-Apim-Subscription-Region", region);
+    }
+
+    let res = retry::send(settings, "azure", builder)
+        .await
+        .map_err(|e| AppError::transport(format!("Request failed: {}", e), &e))?;
+
+    if !res.status().is_success() {
+        let status = res.status();
+        let body = res.text().await.unwrap_or_default();
+        return Err(azure_error(status, &body));
+    }
+
+    let items: Vec<AzureResponseItem> = res
+        .json()
+        .await
+        .map_err(|e| AppError::invalid_response(format!("Parse error: {}", e)))?;
+    let item = items
+        .into_iter()
+        .next()
+        .ok_or_else(|| AppError::invalid_response("Azure 未返回翻译结果"))?;
+
+    Ok(AzureMultiTranslation {
+        detected_source_lang: item.detected_language.map(|d| d.language),
+        translations: item
+            .translations
+            .into_iter()
+            .map(|t| AzureTargetTranslation {
+                target_lang: t.to,
+                text: t.text,
+            })
+            .collect(),
+    })
+}
+
+pub struct AzureEngine;
+
+impl TranslationEngine for AzureEngine {
+    fn id(&self) -> &'static str {
+        "azure"
+    }
+
+    fn capabilities(&self) -> EngineCapabilities {
+        EngineCapabilities {
+            id: self.id(),
+            name: "Azure Translator",
+            kind: EngineKind::Machine,
+            supports_tone: false,
+            supports_streaming: false,
+        }
+    }
+
+    fn validate_config(&self, settings: &AppSettings) -> Result<(), AppError> {
+        if settings.azure_subscription_key.is_none() {
+            return Err(AppError::invalid_config("未配置 Azure Translator 订阅密钥"));
+        }
+        Ok(())
+    }
+
+    fn supported_languages(&self) -> Vec<Language> {
+        languages::supported_by(LANGUAGE_CODES)
+    }
+
+    fn output_config(&self, settings: &AppSettings) -> Vec<String> {
+        vec![
+            settings.azure_text_type.as_str().to_string(),
+            azure_endpoint(settings).to_string(),
+            settings.azure_region.clone().unwrap_or_default(),
+        ]
+    }
+
+    fn translate<'a>(
+        &'a self,
+        client: &'a Client,
+        settings: &'a AppSettings,
+        request: &'a TranslationRequest,
+    ) -> EngineFuture<'a, Translation> {
+        Box::pin(async move {
+            let result = translate_multi(
+                client,
+                settings,
+                &request.text,
+                request.source_lang.as_deref(),
+                std::slice::from_ref(&request.target_lang),
+                settings.azure_text_type,
+            )
+            .await?;
+
+            result
+                .translations
+                .into_iter()
+                .next()
+                .map(|t| Translation::from(t.text))
+                .ok_or_else(|| AppError::invalid_response("Azure 未返回翻译结果"))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_repeats_target_languages() {
+        let targets = ["zh".to_string(), "ja".to_string(), "en".to_string()];
+        let query = translate_query(Some("en"), &targets, AzureTextType::Html);
+        let query: Vec<(&str, &str)> = query.iter().map(|(k, v)| (*k, v.as_str())).collect();
+        assert_eq!(
+            query,
+            [
+                ("api-version", "3.0"),
+                ("textType", "html"),
+                ("from", "en"),
+                ("to", "zh-Hans"),
+                ("to", "ja"),
+                ("to", "en"),
+            ]
+        );
+    }
+
+    #[test]
+    fn query_without_source_lets_azure_detect() {
+        let query = translate_query(None, &["zh-Hant".to_string()], AzureTextType::Plain);
+        assert!(query.iter().all(|(key, _)| *key != "from"));
+        assert!(query.contains(&("textType", "plain".to_string())));
+        assert!(query.contains(&("to", "zh-Hant".to_string())));
+    }
+
+    #[test]
+    fn quota_error_code_maps_to_quota() {
+        let body = r#"{"error":{"code":403001,"message":"The operation was not allowed because the subscription has exceeded its free quota."}}"#;
+        let error = azure_error(StatusCode::FORBIDDEN, body);
+        assert_eq!(error.code, ErrorCode::Quota);
+    }
+
+    #[test]
+    fn unauthorized_maps_to_auth() {
+        let body = r#"{"error":{"code":401000,"message":"The request is not authorized because credentials are missing or invalid."}}"#;
+        assert_eq!(
+            azure_error(StatusCode::UNAUTHORIZED, body).code,
+            ErrorCode::Auth
+        );
+        // Bodies that aren't Azure's error shape fall back to the status
+        assert_eq!(
+            azure_error(StatusCode::UNAUTHORIZED, "Unauthorized").code,
+            ErrorCode::Auth
+        );
+    }
+}
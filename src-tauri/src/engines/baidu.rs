@@ -0,0 +1,217 @@
+//! Baidu Fanyi (百度翻译) engine with MD5 request signing.
+
+use chrono::Utc;
+use md5::{Digest, Md5};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use super::retry;
+use super::{
+    EngineCapabilities, EngineFuture, EngineKind, Translation, TranslationEngine,
+    TranslationRequest,
+};
+use crate::error::{AppError, ErrorCode};
+use crate::languages::{self, CodeTable, Language};
+use crate::AppSettings;
+
+const BAIDU_TRANSLATE_URL: &str = "https://fanyi-api.baidu.com/api/trans/vip/translate";
+
+#[derive(Serialize)]
+struct BaiduRequest<'a> {
+    q: &'a str,
+    from: &'a str,
+    to: &'a str,
+    appid: &'a str,
+    salt: &'a str,
+    sign: &'a str,
+}
+
+#[derive(Deserialize)]
+struct BaiduResponse {
+    trans_result: Option<Vec<BaiduTransResult>>,
+    error_code: Option<String>,
+    error_msg: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BaiduTransResult {
+    dst: String,
+}
+
+pub struct BaiduEngine;
+
+impl TranslationEngine for BaiduEngine {
+    fn id(&self) -> &'static str {
+        "baidu"
+    }
+
+    fn capabilities(&self) -> EngineCapabilities {
+        EngineCapabilities {
+            id: self.id(),
+            name: "Baidu Fanyi",
+            kind: EngineKind::Machine,
+            supports_tone: false,
+            supports_streaming: false,
+        }
+    }
+
+    fn validate_config(&self, settings: &AppSettings) -> Result<(), AppError> {
+        if settings.baidu_app_id.is_none() {
+            return Err(AppError::invalid_config("未配置百度翻译 APP ID"));
+        }
+        if settings.baidu_secret_key.is_none() {
+            return Err(AppError::invalid_config("未配置百度翻译密钥"));
+        }
+        Ok(())
+    }
+
+    fn supported_languages(&self) -> Vec<Language> {
+        languages::supported_by(LANGUAGE_CODES)
+    }
+
+    fn translate<'a>(
+        &'a self,
+        client: &'a Client,
+        settings: &'a AppSettings,
+        request: &'a TranslationRequest,
+    ) -> EngineFuture<'a, Translation> {
+        Box::pin(async move {
+            translate_baidu(client, settings, request)
+                .await
+                .map(Translation::from)
+        })
+    }
+}
+
+/// sign = MD5(appid + q + salt + secret_key), lowercase hex
+fn baidu_sign(app_id: &str, text: &str, salt: &str, secret_key: &str) -> String {
+    let mut hasher = Md5::new();
+    hasher.update(app_id.as_bytes());
+    hasher.update(text.as_bytes());
+    hasher.update(salt.as_bytes());
+    hasher.update(secret_key.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// Baidu uses its own codes for some languages, e.g. `jp` and `kor`
+const LANGUAGE_CODES: CodeTable = &[
+    ("zh", "zh"),
+    ("zh-Hant", "cht"),
+    ("en", "en"),
+    ("ja", "jp"),
+    ("ko", "kor"),
+    ("fr", "fra"),
+    ("de", "de"),
+    ("es", "spa"),
+    ("pt", "pt"),
+    ("it", "it"),
+    ("ru", "ru"),
+    ("ar", "ara"),
+    ("th", "th"),
+    ("vi", "vie"),
+    ("nl", "nl"),
+    ("pl", "pl"),
+];
+
+fn baidu_lang(lang: &str) -> &str {
+    languages::engine_code(LANGUAGE_CODES, lang)
+}
+
+/// Map a Baidu `error_code`, see the API's error code table
+fn error_code(code: &str) -> ErrorCode {
+    match code {
+        // Request timed out, system error
+        "52001" | "52002" => ErrorCode::EngineUnavailable,
+        // Unauthorized user, bad signature, IP not allowed, service disabled
+        "52003" | "54001" | "58000" | "58002" | "90107" => ErrorCode::Auth,
+        "54003" | "54005" => ErrorCode::RateLimited,
+        "54004" => ErrorCode::Quota,
+        _ => ErrorCode::EngineRejected,
+    }
+}
+
+async fn translate_baidu(
+    client: &Client,
+    settings: &AppSettings,
+    request: &TranslationRequest,
+) -> Result<String, AppError> {
+    let app_id = settings
+        .baidu_app_id
+        .as_deref()
+        .ok_or_else(|| AppError::invalid_config("未配置百度翻译 APP ID"))?;
+    let secret_key = settings
+        .baidu_secret_key
+        .as_deref()
+        .ok_or_else(|| AppError::invalid_config("未配置百度翻译密钥"))?;
+
+    let target = baidu_lang(&request.target_lang);
+    let source = request.source_lang.as_deref().map_or("auto", baidu_lang);
+
+    let salt = Utc::now().timestamp_millis().to_string();
+    let sign = baidu_sign(app_id, &request.text, &salt, secret_key);
+
+    let payload = BaiduRequest {
+        q: &request.text,
+        from: source,
+        to: target,
+        appid: app_id,
+        salt: &salt,
+        sign: &sign,
+    };
+
+    let resp_data: BaiduResponse = retry::with_retry(settings, "baidu", || async {
+        let res = client
+            .post(BAIDU_TRANSLATE_URL)
+            .form(&payload)
+            .send()
+            .await
+            .map_err(|e| AppError::transport(format!("Request failed: {}", e), &e))?;
+
+        let mut resp_data: BaiduResponse = res
+            .json()
+            .await
+            .map_err(|e| AppError::invalid_response(format!("Parse error: {}", e)))?;
+
+        match resp_data.error_code.take() {
+            // Baidu reports "52000" for success on some endpoints
+            Some(code) if code != "52000" => {
+                let message = format!(
+                    "Baidu API Error ({}): {}",
+                    code,
+                    resp_data.error_msg.unwrap_or_default()
+                );
+                Err(AppError::new(error_code(&code), message))
+            }
+            _ => Ok(resp_data),
+        }
+    })
+    .await?;
+
+    // Each input line comes back as a separate result
+    let lines: Vec<String> = resp_data
+        .trans_result
+        .unwrap_or_default()
+        .into_iter()
+        .map(|r| r.dst)
+        .collect();
+
+    if lines.is_empty() {
+        return Err(AppError::invalid_response("百度翻译未返回结果"));
+    }
+
+    Ok(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sign_matches_documented_example() {
+        // Example from Baidu's general translation API docs
+        assert_eq!(
+            baidu_sign("2015063000000001", "apple", "1435660288", "12345678"),
+            "f89f9594663708c1605f3d736d01d2d4"
+        );
+    }
+}
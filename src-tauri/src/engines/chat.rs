@@ -0,0 +1,220 @@
+//! Chat-completions wire format shared by Zhipu and OpenAI-compatible endpoints.
+
+use futures_util::StreamExt;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use super::prompt::{build_system_prompt, strip_code_fences};
+use super::retry;
+use super::{DeltaSink, TranslationRequest};
+use crate::error::AppError;
+use crate::glossary::GlossaryEntry;
+use crate::AppSettings;
+
+#[derive(Debug, Serialize)]
+pub struct ChatCompletionRequest {
+    pub model: String,
+    pub messages: Vec<ChatMessage>,
+    pub temperature: f32,
+    pub top_p: f32,
+    pub stream: bool,
+    /// GLM-specific sampling switch, omitted for other providers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub do_sample: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<ChatResponseFormat>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChatResponseFormat {
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+    message: ChatAssistantMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatAssistantMessage {
+    content: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionChunk {
+    choices: Vec<ChatChunkChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChunkChoice {
+    delta: ChatChunkDelta,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChunkDelta {
+    content: Option<String>,
+}
+
+/// Build the system + user message pair used for every translation.
+pub fn translation_messages(
+    glossary: &[GlossaryEntry],
+    request: &TranslationRequest,
+) -> Vec<ChatMessage> {
+    let system_prompt = build_system_prompt(glossary, request);
+    vec![
+        ChatMessage {
+            role: "system".to_string(),
+            content: system_prompt,
+        },
+        ChatMessage {
+            role: "user".to_string(),
+            content: request.text.clone(),
+        },
+    ]
+}
+
+impl ChatCompletionRequest {
+    pub fn for_translation(
+        model: &str,
+        glossary: &[GlossaryEntry],
+        request: &TranslationRequest,
+    ) -> Self {
+        Self {
+            model: model.to_string(),
+            messages: translation_messages(glossary, request),
+            temperature: 0.2,
+            top_p: 0.9,
+            stream: false,
+            do_sample: None,
+            response_format: None,
+        }
+    }
+}
+
+async fn post_chat_completion(
+    client: &Client,
+    settings: &AppSettings,
+    engine: &str,
+    url: &str,
+    api_key: Option<&str>,
+    payload: &ChatCompletionRequest,
+) -> Result<reqwest::Response, AppError> {
+    let mut builder = client.post(url).json(payload);
+    if let Some(api_key) = api_key {
+        builder = builder.header("Authorization", format!("Bearer {}", api_key));
+    }
+
+    let res = retry::send(settings, engine, builder)
+        .await
+        .map_err(|e| AppError::transport(format!("请求失败: {}", e), &e))?;
+
+    if !res.status().is_success() {
+        let status = res.status();
+        let body = res.text().await.unwrap_or_default();
+        return Err(AppError::from_status(
+            status,
+            format!("接口返回错误 ({}): {}", status, body),
+        ));
+    }
+
+    Ok(res)
+}
+
+/// POST a chat-completions payload and return the first choice's content.
+pub async fn send_chat_completion(
+    client: &Client,
+    settings: &AppSettings,
+    engine: &str,
+    url: &str,
+    api_key: Option<&str>,
+    payload: &ChatCompletionRequest,
+) -> Result<String, AppError> {
+    let res = post_chat_completion(client, settings, engine, url, api_key, payload).await?;
+
+    let data: ChatCompletionResponse = res
+        .json()
+        .await
+        .map_err(|e| AppError::invalid_response(format!("解析响应失败: {}", e)))?;
+
+    let content = data
+        .choices
+        .first()
+        .map(|c| c.message.content.as_str())
+        .filter(|content| !content.trim().is_empty())
+        .ok_or_else(|| AppError::invalid_response("翻译失败：未返回内容"))?;
+
+    Ok(strip_code_fences(content))
+}
+
+/// POST a chat-completions payload with `stream: true` and forward each
+/// content delta from the server-sent events to `on_delta`.
+///
+/// Returns the full content once the stream ends. Only the initial request is
+/// retried; a stream that breaks off fails.
+pub async fn stream_chat_completion(
+    client: &Client,
+    settings: &AppSettings,
+    engine: &str,
+    url: &str,
+    api_key: Option<&str>,
+    payload: &mut ChatCompletionRequest,
+    on_delta: DeltaSink<'_>,
+) -> Result<String, AppError> {
+    payload.stream = true;
+    let res = post_chat_completion(client, settings, engine, url, api_key, payload).await?;
+
+    let mut stream = res.bytes_stream();
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut content = String::new();
+
+    'stream: while let Some(chunk) = stream.next().await {
+        let chunk =
+            chunk.map_err(|e| AppError::transport(format!("读取流式响应失败: {}", e), &e))?;
+        buffer.extend_from_slice(&chunk);
+
+        // SSE events are newline-delimited; keep any partial line for the next chunk
+        while let Some(pos) = buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&line);
+            let Some(data) = line.trim().strip_prefix("data:") else {
+                continue;
+            };
+            let data = data.trim();
+            if data == "[DONE]" {
+                break 'stream;
+            }
+
+            let chunk: ChatCompletionChunk = serde_json::from_str(data)
+                .map_err(|e| AppError::invalid_response(format!("解析流式响应失败: {}", e)))?;
+            if let Some(delta) = chunk
+                .choices
+                .into_iter()
+                .next()
+                .and_then(|c| c.delta.content)
+            {
+                if !delta.is_empty() {
+                    on_delta(&delta);
+                    content.push_str(&delta);
+                }
+            }
+        }
+    }
+
+    if content.trim().is_empty() {
+        return Err(AppError::invalid_response("翻译失败：未返回内容"));
+    }
+
+    Ok(strip_code_fences(&content))
+}
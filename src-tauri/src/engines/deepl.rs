@@ -0,0 +1,350 @@
+//! DeepL API engine covering the Free (`api-free.deepl.com`) and Pro
+//! (`api.deepl.com`) endpoints, plus glossary lookup.
+
+use log::info;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use super::retry;
+use super::{
+    EngineCapabilities, EngineFuture, EngineKind, Translation, TranslationEngine,
+    TranslationRequest,
+};
+use crate::error::{AppError, ErrorCode};
+use crate::languages::{self, CodeTable, Language};
+use crate::AppSettings;
+
+const DEEPL_FREE_ENDPOINT: &str = "https://api-free.deepl.com";
+const DEEPL_PRO_ENDPOINT: &str = "https://api.deepl.com";
+
+#[derive(Serialize)]
+struct DeeplRequest<'a> {
+    text: Vec<&'a str>,
+    target_lang: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_lang: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    formality: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    glossary_id: Option<&'a str>,
+}
+
+#[derive(Deserialize)]
+struct DeeplResponse {
+    translations: Vec<DeeplTranslation>,
+}
+
+#[derive(Deserialize)]
+struct DeeplTranslation {
+    text: String,
+}
+
+#[derive(Deserialize)]
+struct DeeplErrorBody {
+    message: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct DeeplGlossaryList {
+    glossaries: Vec<DeeplGlossary>,
+}
+
+/// Glossary metadata as returned by `/v2/glossaries`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeeplGlossary {
+    pub glossary_id: String,
+    pub name: String,
+    pub source_lang: String,
+    pub target_lang: String,
+    #[serde(default)]
+    pub entry_count: u64,
+}
+
+/// Free-plan keys carry a `:fx` suffix; a configured endpoint always wins so
+/// a local stand-in server can be used.
+fn deepl_endpoint(api_key: &str, endpoint: Option<&str>) -> String {
+    match endpoint {
+        Some(endpoint) => endpoint.trim().trim_end_matches('/').to_string(),
+        None if api_key.ends_with(":fx") => DEEPL_FREE_ENDPOINT.to_string(),
+        None => DEEPL_PRO_ENDPOINT.to_string(),
+    }
+}
+
+const LANGUAGE_CODES: CodeTable = &[
+    ("zh", "ZH-HANS"),
+    ("zh-Hant", "ZH-HANT"),
+    ("en", "EN-US"),
+    ("ja", "JA"),
+    ("ko", "KO"),
+    ("fr", "FR"),
+    ("de", "DE"),
+    ("es", "ES"),
+    ("pt", "PT-PT"),
+    ("it", "IT"),
+    ("ru", "RU"),
+    ("ar", "AR"),
+    ("id", "ID"),
+    ("tr", "TR"),
+    ("nl", "NL"),
+    ("pl", "PL"),
+];
+
+fn deepl_target_lang(target_lang: &str) -> String {
+    languages::engine_code(LANGUAGE_CODES, target_lang.trim()).to_ascii_uppercase()
+}
+
+/// Source languages take no regional variant, e.g. `EN` rather than `EN-US`
+fn deepl_source_lang(source_lang: &str) -> String {
+    let target = deepl_target_lang(source_lang);
+    target
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Formal → more, Casual → less. The `prefer_` variants fall back silently
+/// for target languages without formality support instead of failing.
+fn deepl_formality(tone: &str) -> Option<&'static str> {
+    match tone {
+        "Formal" => Some("prefer_more"),
+        "Casual" => Some("prefer_less"),
+        _ => None,
+    }
+}
+
+async fn deepl_error(res: reqwest::Response) -> AppError {
+    let status = res.status();
+    let body = res.text().await.unwrap_or_default();
+    let message = serde_json::from_str::<DeeplErrorBody>(&body)
+        .ok()
+        .and_then(|b| b.message)
+        .unwrap_or(body);
+    match status.as_u16() {
+        403 => AppError::new(
+            ErrorCode::Auth,
+            format!("DeepL 鉴权失败，请检查 API Key: {}", message),
+        ),
+        456 => AppError::new(
+            ErrorCode::Quota,
+            format!("DeepL 字符额度已用尽: {}", message),
+        ),
+        _ => AppError::from_status(status, format!("DeepL API Error ({}): {}", status, message)),
+    }
+}
+
+fn auth_header(api_key: &str) -> String {
+    format!("DeepL-Auth-Key {}", api_key)
+}
+
+/// List the glossaries available to this API key.
+pub async fn list_glossaries(
+    client: &Client,
+    api_key: &str,
+    endpoint: Option<&str>,
+) -> Result<Vec<DeeplGlossary>, AppError> {
+    let url = format!("{}/v2/glossaries", deepl_endpoint(api_key, endpoint));
+    let res = client
+        .get(&url)
+        .header("Authorization", auth_header(api_key))
+        .send()
+        .await
+        .map_err(|e| AppError::transport(format!("Request failed: {}", e), &e))?;
+
+    if !res.status().is_success() {
+        return Err(deepl_error(res).await);
+    }
+
+    let data: DeeplGlossaryList = res
+        .json()
+        .await
+        .map_err(|e| AppError::invalid_response(format!("Parse error: {}", e)))?;
+    Ok(data.glossaries)
+}
+
+/// Fetch metadata for a single glossary, used to learn its language pair.
+pub async fn get_glossary(
+    client: &Client,
+    api_key: &str,
+    endpoint: Option<&str>,
+    glossary_id: &str,
+) -> Result<DeeplGlossary, AppError> {
+    let url = format!(
+        "{}/v2/glossaries/{}",
+        deepl_endpoint(api_key, endpoint),
+        glossary_id
+    );
+    let res = client
+        .get(&url)
+        .header("Authorization", auth_header(api_key))
+        .send()
+        .await
+        .map_err(|e| AppError::transport(format!("Request failed: {}", e), &e))?;
+
+    if !res.status().is_success() {
+        return Err(deepl_error(res).await);
+    }
+
+    res.json()
+        .await
+        .map_err(|e| AppError::invalid_response(format!("Parse error: {}", e)))
+}
+
+pub struct DeeplEngine;
+
+impl TranslationEngine for DeeplEngine {
+    fn id(&self) -> &'static str {
+        "deepl"
+    }
+
+    fn capabilities(&self) -> EngineCapabilities {
+        EngineCapabilities {
+            id: self.id(),
+            name: "DeepL",
+            kind: EngineKind::Machine,
+            supports_tone: true,
+            supports_streaming: false,
+        }
+    }
+
+    fn validate_config(&self, settings: &AppSettings) -> Result<(), AppError> {
+        if settings.deepl_api_key.is_none() {
+            return Err(AppError::invalid_config("未配置 DeepL API Key"));
+        }
+        Ok(())
+    }
+
+    fn supported_languages(&self) -> Vec<Language> {
+        languages::supported_by(LANGUAGE_CODES)
+    }
+
+    fn output_config(&self, settings: &AppSettings) -> Vec<String> {
+        let endpoint = settings
+            .deepl_api_key
+            .as_deref()
+            .map(|api_key| deepl_endpoint(api_key, settings.deepl_endpoint.as_deref()));
+        vec![
+            endpoint.unwrap_or_default(),
+            settings
+                .deepl_glossary
+                .as_ref()
+                .map(|glossary| glossary.glossary_id.clone())
+                .unwrap_or_default(),
+        ]
+    }
+
+    fn translate<'a>(
+        &'a self,
+        client: &'a Client,
+        settings: &'a AppSettings,
+        request: &'a TranslationRequest,
+    ) -> EngineFuture<'a, Translation> {
+        Box::pin(async move {
+            translate_deepl(client, settings, request)
+                .await
+                .map(Translation::from)
+        })
+    }
+}
+
+async fn translate_deepl(
+    client: &Client,
+    settings: &AppSettings,
+    request: &TranslationRequest,
+) -> Result<String, AppError> {
+    let api_key = settings
+        .deepl_api_key
+        .as_deref()
+        .ok_or_else(|| AppError::invalid_config("未配置 DeepL API Key"))?;
+    let endpoint = deepl_endpoint(api_key, settings.deepl_endpoint.as_deref());
+    let target = deepl_target_lang(&request.target_lang);
+    let requested_source = request.source_lang.as_deref().map(deepl_source_lang);
+
+    // DeepL only accepts a glossary together with its source language, so it
+    // is applied when the requested language pair matches the glossary's.
+    let glossary = settings.deepl_glossary.as_ref().filter(|g| {
+        let source_matches = requested_source
+            .as_deref()
+            .is_none_or(|source| source.eq_ignore_ascii_case(&g.source_lang));
+        source_matches
+            && target.split('-').next() == Some(g.target_lang.to_ascii_uppercase().as_str())
+    });
+    let source = requested_source.or_else(|| glossary.map(|g| g.source_lang.to_ascii_uppercase()));
+
+    let payload = DeeplRequest {
+        text: vec![&request.text],
+        target_lang: &target,
+        source_lang: source.as_deref(),
+        formality: deepl_formality(&request.tone),
+        glossary_id: glossary.map(|g| g.glossary_id.as_str()),
+    };
+
+    info!(
+        "[deepl] POST {}/v2/translate target: {}, glossary: {:?}",
+        endpoint, target, payload.glossary_id
+    );
+
+    let request = client
+        .post(format!("{}/v2/translate", endpoint))
+        .header("Authorization", auth_header(api_key))
+        .json(&payload);
+    let res = retry::send(settings, "deepl", request)
+        .await
+        .map_err(|e| AppError::transport(format!("Request failed: {}", e), &e))?;
+
+    if !res.status().is_success() {
+        return Err(deepl_error(res).await);
+    }
+
+    let data: DeeplResponse = res
+        .json()
+        .await
+        .map_err(|e| AppError::invalid_response(format!("Parse error: {}", e)))?;
+
+    data.translations
+        .into_iter()
+        .next()
+        .map(|t| t.text)
+        .ok_or_else(|| AppError::invalid_response("DeepL 未返回翻译结果"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn endpoint_follows_key_plan_unless_overridden() {
+        assert_eq!(deepl_endpoint("abc:fx", None), DEEPL_FREE_ENDPOINT);
+        assert_eq!(deepl_endpoint("abc", None), DEEPL_PRO_ENDPOINT);
+        assert_eq!(
+            deepl_endpoint("abc:fx", Some(" http://127.0.0.1:3000/ ")),
+            "http://127.0.0.1:3000"
+        );
+        assert_eq!(
+            deepl_endpoint("abc", Some("http://127.0.0.1:3000")),
+            "http://127.0.0.1:3000"
+        );
+    }
+
+    #[test]
+    fn source_lang_drops_regional_variant() {
+        assert_eq!(deepl_source_lang("en"), "EN");
+        assert_eq!(deepl_source_lang("zh"), "ZH");
+        assert_eq!(deepl_source_lang("zh-Hant"), "ZH");
+        assert_eq!(deepl_source_lang("pt"), "PT");
+        assert_eq!(deepl_source_lang("ja"), "JA");
+    }
+
+    #[test]
+    fn target_lang_keeps_regional_variant() {
+        assert_eq!(deepl_target_lang("en"), "EN-US");
+        assert_eq!(deepl_target_lang("zh-Hant"), "ZH-HANT");
+    }
+
+    #[test]
+    fn formality_follows_tone() {
+        assert_eq!(deepl_formality("Formal"), Some("prefer_more"));
+        assert_eq!(deepl_formality("Casual"), Some("prefer_less"));
+        assert_eq!(deepl_formality("Neutral"), None);
+    }
+}
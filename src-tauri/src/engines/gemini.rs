@@ -0,0 +1,265 @@
+//! Google Gemini `generateContent` engine.
+
+use log::{info, warn};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use super::prompt::{build_system_prompt, strip_code_fences};
+use super::retry;
+use super::{
+    EngineCapabilities, EngineFuture, EngineKind, Translation, TranslationEngine,
+    TranslationRequest,
+};
+use crate::error::{AppError, ErrorCode};
+use crate::AppSettings;
+
+pub const DEFAULT_GEMINI_ENDPOINT: &str = "https://generativelanguage.googleapis.com";
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GenerateContentRequest<'a> {
+    system_instruction: GeminiContent<'a>,
+    contents: Vec<GeminiContent<'a>>,
+    generation_config: GenerationConfig,
+}
+
+#[derive(Debug, Serialize)]
+struct GeminiContent<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    role: Option<&'a str>,
+    parts: Vec<GeminiPart<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct GeminiPart<'a> {
+    text: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GenerationConfig {
+    temperature: f32,
+    top_p: f32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GenerateContentResponse {
+    #[serde(default)]
+    candidates: Vec<Candidate>,
+    prompt_feedback: Option<PromptFeedback>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Candidate {
+    content: Option<CandidateContent>,
+    finish_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CandidateContent {
+    #[serde(default)]
+    parts: Vec<CandidatePart>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CandidatePart {
+    text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PromptFeedback {
+    block_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiErrorResponse {
+    error: GeminiError,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiError {
+    status: Option<String>,
+    message: String,
+}
+
+/// Finish reasons that mean the output was withheld by Gemini's filters.
+fn is_blocked_finish_reason(reason: &str) -> bool {
+    matches!(
+        reason,
+        "SAFETY" | "RECITATION" | "BLOCKLIST" | "PROHIBITED_CONTENT" | "SPII"
+    )
+}
+
+fn blocked_error(reason: &str) -> AppError {
+    AppError::new(
+        ErrorCode::ContentBlocked,
+        format!("Gemini 因安全策略拦截了本次翻译 ({})", reason),
+    )
+}
+
+pub struct GeminiEngine;
+
+impl TranslationEngine for GeminiEngine {
+    fn id(&self) -> &'static str {
+        "gemini"
+    }
+
+    fn capabilities(&self) -> EngineCapabilities {
+        EngineCapabilities {
+            id: self.id(),
+            name: "Google Gemini",
+            kind: EngineKind::Llm,
+            supports_tone: true,
+            supports_streaming: false,
+        }
+    }
+
+    fn validate_config(&self, settings: &AppSettings) -> Result<(), AppError> {
+        if settings.gemini_api_key.is_none() {
+            return Err(AppError::invalid_config("未配置 Gemini API Key"));
+        }
+        if settings.gemini_model.is_none() {
+            return Err(AppError::invalid_config("未配置 Gemini 模型名称"));
+        }
+        Ok(())
+    }
+
+    fn model<'a>(&self, settings: &'a AppSettings) -> Option<&'a str> {
+        settings.gemini_model.as_deref()
+    }
+
+    fn output_config(&self, settings: &AppSettings) -> Vec<String> {
+        vec![gemini_endpoint(settings).to_string()]
+    }
+
+    fn translate<'a>(
+        &'a self,
+        client: &'a Client,
+        settings: &'a AppSettings,
+        request: &'a TranslationRequest,
+    ) -> EngineFuture<'a, Translation> {
+        Box::pin(async move {
+            translate_gemini(client, settings, request)
+                .await
+                .map(Translation::from)
+        })
+    }
+}
+
+fn gemini_endpoint(settings: &AppSettings) -> &str {
+    settings
+        .gemini_endpoint
+        .as_deref()
+        .unwrap_or(DEFAULT_GEMINI_ENDPOINT)
+        .trim_end_matches('/')
+}
+
+async fn translate_gemini(
+    client: &Client,
+    settings: &AppSettings,
+    request: &TranslationRequest,
+) -> Result<String, AppError> {
+    let api_key = settings
+        .gemini_api_key
+        .as_deref()
+        .ok_or_else(|| AppError::invalid_config("未配置 Gemini API Key"))?;
+    let model = settings
+        .gemini_model
+        .as_deref()
+        .ok_or_else(|| AppError::invalid_config("未配置 Gemini 模型名称"))?;
+    let url = format!(
+        "{}/v1beta/models/{}:generateContent",
+        gemini_endpoint(settings),
+        model
+    );
+
+    info!(
+        "[gemini] POST {} text length: {}, target: {}, tone: {}",
+        url,
+        request.text.len(),
+        request.target_lang,
+        request.tone
+    );
+
+    let system_prompt = build_system_prompt(&settings.glossary, request);
+    let payload = GenerateContentRequest {
+        system_instruction: GeminiContent {
+            role: None,
+            parts: vec![GeminiPart {
+                text: &system_prompt,
+            }],
+        },
+        contents: vec![GeminiContent {
+            role: Some("user"),
+            parts: vec![GeminiPart {
+                text: &request.text,
+            }],
+        }],
+        generation_config: GenerationConfig {
+            temperature: 0.2,
+            top_p: 0.9,
+        },
+    };
+
+    let request = client
+        .post(&url)
+        .header("x-goog-api-key", api_key)
+        .json(&payload);
+    let res = retry::send(settings, "gemini", request)
+        .await
+        .map_err(|e| AppError::transport(format!("请求失败: {}", e), &e))?;
+
+    if !res.status().is_success() {
+        let status = res.status();
+        let body = res.text().await.unwrap_or_default();
+        return Err(AppError::from_status(
+            status,
+            match serde_json::from_str::<GeminiErrorResponse>(&body) {
+                Ok(err) => format!(
+                    "Gemini API Error ({}): {}",
+                    err.error.status.unwrap_or_else(|| status.to_string()),
+                    err.error.message
+                ),
+                Err(_) => format!("接口返回错误 ({}): {}", status, body),
+            },
+        ));
+    }
+
+    let data: GenerateContentResponse = res
+        .json()
+        .await
+        .map_err(|e| AppError::invalid_response(format!("解析响应失败: {}", e)))?;
+
+    // The whole prompt was rejected before generation started
+    if let Some(reason) = data.prompt_feedback.and_then(|f| f.block_reason) {
+        warn!("[gemini] Prompt blocked: {}", reason);
+        return Err(blocked_error(&reason));
+    }
+
+    let candidate = data
+        .candidates
+        .into_iter()
+        .next()
+        .ok_or_else(|| AppError::invalid_response("翻译失败：未返回内容"))?;
+
+    if let Some(reason) = candidate.finish_reason.as_deref() {
+        if is_blocked_finish_reason(reason) {
+            warn!("[gemini] Candidate blocked: {}", reason);
+            return Err(blocked_error(reason));
+        }
+    }
+
+    let content: String = candidate
+        .content
+        .map(|c| c.parts.into_iter().filter_map(|p| p.text).collect())
+        .unwrap_or_default();
+
+    if content.trim().is_empty() {
+        return Err(AppError::invalid_response("翻译失败：未返回内容"));
+    }
+
+    Ok(strip_code_fences(&content))
+}
@@ -0,0 +1,190 @@
+//! Self-hosted LibreTranslate engine.
+
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use super::retry;
+use super::{
+    EngineCapabilities, EngineFuture, EngineKind, Translation, TranslationEngine,
+    TranslationRequest,
+};
+use crate::error::AppError;
+use crate::languages::{self, CodeTable, Language};
+use crate::AppSettings;
+
+#[derive(Serialize)]
+struct LibreRequest<'a> {
+    q: &'a str,
+    source: &'a str,
+    target: &'a str,
+    format: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    api_key: Option<&'a str>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LibreResponse {
+    translated_text: String,
+}
+
+#[derive(Deserialize)]
+struct LibreErrorResponse {
+    error: String,
+}
+
+/// A language supported by the instance and the targets it can translate into
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibreLanguage {
+    pub code: String,
+    pub name: String,
+    #[serde(default)]
+    pub targets: Vec<String>,
+}
+
+fn libretranslate_url(settings: &AppSettings) -> Result<&str, AppError> {
+    settings
+        .libretranslate_url
+        .as_deref()
+        .map(|url| url.trim_end_matches('/'))
+        .ok_or_else(|| AppError::invalid_config("未配置 LibreTranslate 服务地址"))
+}
+
+async fn libre_error(res: reqwest::Response) -> AppError {
+    let status = res.status();
+    let body = res.text().await.unwrap_or_default();
+    let message = match serde_json::from_str::<LibreErrorResponse>(&body) {
+        Ok(err) => err.error,
+        Err(_) => body,
+    };
+    AppError::from_status(
+        status,
+        format!("LibreTranslate Error ({}): {}", status, message),
+    )
+}
+
+/// Fetch the language pairs the configured instance supports.
+pub async fn list_languages(
+    client: &Client,
+    settings: &AppSettings,
+) -> Result<Vec<LibreLanguage>, AppError> {
+    let url = format!("{}/languages", libretranslate_url(settings)?);
+    let res = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| AppError::transport(format!("Request failed: {}", e), &e))?;
+
+    if !res.status().is_success() {
+        return Err(libre_error(res).await);
+    }
+
+    res.json()
+        .await
+        .map_err(|e| AppError::invalid_response(format!("Parse error: {}", e)))
+}
+
+pub struct LibreTranslateEngine;
+
+impl TranslationEngine for LibreTranslateEngine {
+    fn id(&self) -> &'static str {
+        "libretranslate"
+    }
+
+    fn capabilities(&self) -> EngineCapabilities {
+        EngineCapabilities {
+            id: self.id(),
+            name: "LibreTranslate",
+            kind: EngineKind::Machine,
+            supports_tone: false,
+            supports_streaming: false,
+        }
+    }
+
+    fn validate_config(&self, settings: &AppSettings) -> Result<(), AppError> {
+        libretranslate_url(settings).map(|_| ())
+    }
+
+    fn supported_languages(&self) -> Vec<Language> {
+        languages::supported_by(LANGUAGE_CODES)
+    }
+
+    fn output_config(&self, settings: &AppSettings) -> Vec<String> {
+        settings.libretranslate_url.iter().cloned().collect()
+    }
+
+    fn translate<'a>(
+        &'a self,
+        client: &'a Client,
+        settings: &'a AppSettings,
+        request: &'a TranslationRequest,
+    ) -> EngineFuture<'a, Translation> {
+        Box::pin(async move {
+            translate_libretranslate(client, settings, request)
+                .await
+                .map(Translation::from)
+        })
+    }
+}
+
+/// Codes of the language models shipped with LibreTranslate; an instance may
+/// have fewer installed, see [`list_languages`]
+const LANGUAGE_CODES: CodeTable = &[
+    ("zh", "zh"),
+    ("zh-Hant", "zt"),
+    ("en", "en"),
+    ("ja", "ja"),
+    ("ko", "ko"),
+    ("fr", "fr"),
+    ("de", "de"),
+    ("es", "es"),
+    ("pt", "pt"),
+    ("it", "it"),
+    ("ru", "ru"),
+    ("ar", "ar"),
+    ("id", "id"),
+    ("tr", "tr"),
+    ("nl", "nl"),
+    ("pl", "pl"),
+];
+
+fn libre_lang(lang: &str) -> &str {
+    languages::engine_code(LANGUAGE_CODES, lang)
+}
+
+async fn translate_libretranslate(
+    client: &Client,
+    settings: &AppSettings,
+    request: &TranslationRequest,
+) -> Result<String, AppError> {
+    let base_url = libretranslate_url(settings)?;
+
+    let target = libre_lang(&request.target_lang);
+    let source = request.source_lang.as_deref().map_or("auto", libre_lang);
+
+    let payload = LibreRequest {
+        q: &request.text,
+        source,
+        target,
+        format: "text",
+        api_key: settings.libretranslate_api_key.as_deref(),
+    };
+
+    let request = client
+        .post(format!("{}/translate", base_url))
+        .json(&payload);
+    let res = retry::send(settings, "libretranslate", request)
+        .await
+        .map_err(|e| AppError::transport(format!("Request failed: {}", e), &e))?;
+
+    if !res.status().is_success() {
+        return Err(libre_error(res).await);
+    }
+
+    let data: LibreResponse = res
+        .json()
+        .await
+        .map_err(|e| AppError::invalid_response(format!("Parse error: {}", e)))?;
+
+    Ok(data.translated_text)
+}
@@ -0,0 +1,196 @@
+//! Translation engines and the registry that maps engine ids to them.
+//!
+//! Each provider lives in its own module and implements [`TranslationEngine`].
+//! Adding a provider means writing that module and listing it in [`ENGINES`];
+//! `get_translation` and `set_active_engine` only go through the registry.
+
+mod anthropic;
+pub mod azure;
+mod baidu;
+mod chat;
+pub mod deepl;
+mod gemini;
+pub mod libretranslate;
+pub mod ollama;
+mod openai;
+mod prompt;
+pub mod retry;
+pub mod tencent;
+mod youdao;
+mod zhipu;
+
+use std::future::Future;
+use std::pin::Pin;
+
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::glossary::GlossaryViolation;
+use crate::languages::{self, Language};
+use crate::AppSettings;
+
+/// Boxed future returned by engines so the trait stays object safe.
+pub type EngineFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, AppError>> + Send + 'a>>;
+
+/// A single translation call, independent of the engine that serves it.
+#[derive(Debug, Clone)]
+pub struct TranslationRequest {
+    pub text: String,
+    /// `None` lets the engine detect the source language
+    pub source_lang: Option<String>,
+    pub target_lang: String,
+    pub tone: String,
+}
+
+/// Output of a translation call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Translation {
+    pub text: String,
+    /// Engine that produced the text; differs from the active engine after a fallback
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub engine: Option<String>,
+    /// Dictionary details, only returned by engines that support word lookup
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dictionary: Option<DictionaryEntry>,
+    /// Glossary terms the output doesn't use, filled in after translating
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub glossary_violations: Vec<GlossaryViolation>,
+    /// Target chosen by auto direction when it replaced the requested one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_target_lang: Option<String>,
+}
+
+impl From<String> for Translation {
+    fn from(text: String) -> Self {
+        Self {
+            text,
+            engine: None,
+            dictionary: None,
+            glossary_violations: Vec::new(),
+            auto_target_lang: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DictionaryEntry {
+    pub phonetic: Option<String>,
+    pub us_phonetic: Option<String>,
+    pub uk_phonetic: Option<String>,
+    /// Short definitions, e.g. "n. 苹果"
+    pub explains: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EngineKind {
+    /// Prompted large language model
+    Llm,
+    /// Classic machine translation API
+    Machine,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EngineCapabilities {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub kind: EngineKind,
+    /// Whether the tone selection changes the output
+    pub supports_tone: bool,
+    /// Whether `translate_stream` yields incremental output
+    pub supports_streaming: bool,
+}
+
+/// Receives each chunk of text as a streaming engine produces it.
+pub type DeltaSink<'a> = &'a (dyn Fn(&str) + Send + Sync);
+
+pub trait TranslationEngine: Send + Sync {
+    /// Stable id stored in `AppSettings::active_engine`
+    fn id(&self) -> &'static str;
+
+    fn capabilities(&self) -> EngineCapabilities;
+
+    /// Check that the settings hold everything this engine needs to run
+    fn validate_config(&self, settings: &AppSettings) -> Result<(), AppError>;
+
+    /// Model the engine is configured to use, for engines where it is selectable
+    fn model<'a>(&self, _settings: &'a AppSettings) -> Option<&'a str> {
+        None
+    }
+
+    /// Settings other than the model that change the output, e.g. the
+    /// endpoint or a glossary id. Part of the cache key.
+    fn output_config(&self, _settings: &AppSettings) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Target languages offered in the UI. LLM engines accept every registry
+    /// language; machine translation engines list those in their code table.
+    fn supported_languages(&self) -> Vec<Language> {
+        languages::LANGUAGES.to_vec()
+    }
+
+    /// Translate through `client`, the shared client from [`crate::http`]
+    fn translate<'a>(
+        &'a self,
+        client: &'a Client,
+        settings: &'a AppSettings,
+        request: &'a TranslationRequest,
+    ) -> EngineFuture<'a, Translation>;
+
+    /// Translate while reporting partial output through `on_delta`.
+    ///
+    /// Engines without a streaming API deliver the whole result as a single delta.
+    fn translate_stream<'a>(
+        &'a self,
+        client: &'a Client,
+        settings: &'a AppSettings,
+        request: &'a TranslationRequest,
+        on_delta: DeltaSink<'a>,
+    ) -> EngineFuture<'a, Translation> {
+        Box::pin(async move {
+            let translation = self.translate(client, settings, request).await?;
+            on_delta(&translation.text);
+            Ok(translation)
+        })
+    }
+}
+
+/// All available engines. The first entry is the default.
+static ENGINES: &[&dyn TranslationEngine] = &[
+    &zhipu::ZhipuEngine,
+    &tencent::TencentEngine,
+    &openai::OpenAiEngine,
+    &ollama::OllamaEngine,
+    &deepl::DeeplEngine,
+    &baidu::BaiduEngine,
+    &youdao::YoudaoEngine,
+    &azure::AzureEngine,
+    &anthropic::AnthropicEngine,
+    &gemini::GeminiEngine,
+    &libretranslate::LibreTranslateEngine,
+];
+
+pub fn find_engine(id: &str) -> Option<&'static dyn TranslationEngine> {
+    ENGINES.iter().copied().find(|engine| engine.id() == id)
+}
+
+pub fn default_engine() -> &'static dyn TranslationEngine {
+    ENGINES[0]
+}
+
+pub fn all_engines() -> impl Iterator<Item = &'static dyn TranslationEngine> {
+    ENGINES.iter().copied()
+}
+
+/// Accepts either a base URL or the full endpoint URL ending in `path`, and
+/// returns the latter, so users can paste whichever their provider documents.
+fn endpoint_url(base_url: &str, path: &str) -> String {
+    let base_url = base_url.trim().trim_end_matches('/');
+    if base_url.ends_with(path) {
+        base_url.to_string()
+    } else {
+        format!("{}{}", base_url, path)
+    }
+}
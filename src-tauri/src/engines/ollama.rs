@@ -0,0 +1,175 @@
+//! Local Ollama engine (`/api/chat`) for fully offline translation.
+
+use log::info;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use super::chat::{translation_messages, ChatMessage};
+use super::prompt::strip_code_fences;
+use super::retry;
+use super::{
+    EngineCapabilities, EngineFuture, EngineKind, Translation, TranslationEngine,
+    TranslationRequest,
+};
+use crate::error::AppError;
+use crate::AppSettings;
+
+pub const DEFAULT_OLLAMA_HOST: &str = "http://localhost:11434";
+
+#[derive(Debug, Serialize)]
+struct OllamaChatRequest {
+    model: String,
+    messages: Vec<ChatMessage>,
+    stream: bool,
+    options: OllamaOptions,
+}
+
+#[derive(Debug, Serialize)]
+struct OllamaOptions {
+    temperature: f32,
+    top_p: f32,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaChatResponse {
+    message: OllamaMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaMessage {
+    content: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaTagsResponse {
+    models: Vec<OllamaModel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaModel {
+    name: String,
+}
+
+fn ollama_host(settings: &AppSettings) -> &str {
+    settings
+        .ollama_host
+        .as_deref()
+        .unwrap_or(DEFAULT_OLLAMA_HOST)
+        .trim_end_matches('/')
+}
+
+/// List the models installed on the Ollama server at `host`.
+pub async fn list_models(client: &Client, host: &str) -> Result<Vec<String>, AppError> {
+    let url = format!("{}/api/tags", host.trim().trim_end_matches('/'));
+    let res = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| AppError::transport(format!("无法连接 Ollama ({}): {}", url, e), &e))?;
+
+    if !res.status().is_success() {
+        let status = res.status();
+        let body = res.text().await.unwrap_or_default();
+        return Err(AppError::from_status(
+            status,
+            format!("Ollama 返回错误 ({}): {}", status, body),
+        ));
+    }
+
+    let data: OllamaTagsResponse = res
+        .json()
+        .await
+        .map_err(|e| AppError::invalid_response(format!("解析 Ollama 模型列表失败: {}", e)))?;
+
+    Ok(data.models.into_iter().map(|m| m.name).collect())
+}
+
+pub struct OllamaEngine;
+
+impl TranslationEngine for OllamaEngine {
+    fn id(&self) -> &'static str {
+        "ollama"
+    }
+
+    fn capabilities(&self) -> EngineCapabilities {
+        EngineCapabilities {
+            id: self.id(),
+            name: "Ollama (Local)",
+            kind: EngineKind::Llm,
+            supports_tone: true,
+            supports_streaming: false,
+        }
+    }
+
+    fn validate_config(&self, settings: &AppSettings) -> Result<(), AppError> {
+        if settings.ollama_model.is_none() {
+            return Err(AppError::invalid_config("未选择 Ollama 模型"));
+        }
+        Ok(())
+    }
+
+    fn model<'a>(&self, settings: &'a AppSettings) -> Option<&'a str> {
+        settings.ollama_model.as_deref()
+    }
+
+    fn output_config(&self, settings: &AppSettings) -> Vec<String> {
+        vec![ollama_host(settings).to_string()]
+    }
+
+    fn translate<'a>(
+        &'a self,
+        client: &'a Client,
+        settings: &'a AppSettings,
+        request: &'a TranslationRequest,
+    ) -> EngineFuture<'a, Translation> {
+        Box::pin(async move {
+            let model = settings
+                .ollama_model
+                .as_deref()
+                .ok_or_else(|| AppError::invalid_config("未选择 Ollama 模型"))?;
+            let url = format!("{}/api/chat", ollama_host(settings));
+
+            info!(
+                "[ollama] POST {} model: {}, text length: {}, target: {}, tone: {}",
+                url,
+                model,
+                request.text.len(),
+                request.target_lang,
+                request.tone
+            );
+
+            let payload = OllamaChatRequest {
+                model: model.to_string(),
+                messages: translation_messages(&settings.glossary, request),
+                stream: false,
+                options: OllamaOptions {
+                    temperature: 0.2,
+                    top_p: 0.9,
+                },
+            };
+
+            let request = client.post(&url).json(&payload);
+            let res = retry::send(settings, self.id(), request)
+                .await
+                .map_err(|e| {
+                    AppError::transport(format!("无法连接 Ollama ({}): {}", url, e), &e)
+                })?;
+
+            if !res.status().is_success() {
+                let status = res.status();
+                let body = res.text().await.unwrap_or_default();
+                return Err(AppError::from_status(
+                    status,
+                    format!("Ollama 返回错误 ({}): {}", status, body),
+                ));
+            }
+
+            let data: OllamaChatResponse = res
+                .json()
+                .await
+                .map_err(|e| AppError::invalid_response(format!("解析响应失败: {}", e)))?;
+
+            Ok(strip_code_fences(&data.message.content).into())
+        })
+    }
+}
@@ -0,0 +1,134 @@
+//! Engine for any endpoint exposing the OpenAI `/v1/chat/completions` shape
+//! (OpenAI itself, vLLM, LiteLLM, internal gateways, local mock servers).
+
+use log::info;
+use reqwest::Client;
+
+use super::chat::{send_chat_completion, stream_chat_completion, ChatCompletionRequest};
+use super::{
+    endpoint_url, DeltaSink, EngineCapabilities, EngineFuture, EngineKind, Translation,
+    TranslationEngine, TranslationRequest,
+};
+use crate::error::AppError;
+use crate::AppSettings;
+
+pub struct OpenAiEngine;
+
+impl OpenAiEngine {
+    /// Resolve the chat-completions URL and model name from settings
+    fn target(settings: &AppSettings) -> Result<(String, &str), AppError> {
+        let base_url = settings
+            .openai_base_url
+            .as_deref()
+            .ok_or_else(|| AppError::invalid_config("未配置 OpenAI 兼容接口的 Base URL"))?;
+        let model = settings
+            .openai_model
+            .as_deref()
+            .ok_or_else(|| AppError::invalid_config("未配置 OpenAI 兼容接口的模型名称"))?;
+        // Either a base URL (`http://host:8000/v1`) or the full URL
+        Ok((endpoint_url(base_url, "/chat/completions"), model))
+    }
+}
+
+impl TranslationEngine for OpenAiEngine {
+    fn id(&self) -> &'static str {
+        "openai"
+    }
+
+    fn capabilities(&self) -> EngineCapabilities {
+        EngineCapabilities {
+            id: self.id(),
+            name: "OpenAI Compatible",
+            kind: EngineKind::Llm,
+            supports_tone: true,
+            supports_streaming: true,
+        }
+    }
+
+    fn validate_config(&self, settings: &AppSettings) -> Result<(), AppError> {
+        if settings.openai_base_url.is_none() {
+            return Err(AppError::invalid_config(
+                "未配置 OpenAI 兼容接口的 Base URL",
+            ));
+        }
+        if settings.openai_model.is_none() {
+            return Err(AppError::invalid_config("未配置 OpenAI 兼容接口的模型名称"));
+        }
+        Ok(())
+    }
+
+    fn model<'a>(&self, settings: &'a AppSettings) -> Option<&'a str> {
+        settings.openai_model.as_deref()
+    }
+
+    fn output_config(&self, settings: &AppSettings) -> Vec<String> {
+        settings.openai_base_url.iter().cloned().collect()
+    }
+
+    fn translate<'a>(
+        &'a self,
+        client: &'a Client,
+        settings: &'a AppSettings,
+        request: &'a TranslationRequest,
+    ) -> EngineFuture<'a, Translation> {
+        Box::pin(async move {
+            let (url, model) = Self::target(settings)?;
+
+            info!(
+                "[openai] POST {} model: {}, text length: {}, target: {}, tone: {}",
+                url,
+                model,
+                request.text.len(),
+                request.target_lang,
+                request.tone
+            );
+
+            // Self-hosted gateways often run without auth, so the key is optional
+            let payload =
+                ChatCompletionRequest::for_translation(model, &settings.glossary, request);
+            send_chat_completion(
+                client,
+                settings,
+                self.id(),
+                &url,
+                settings.openai_api_key.as_deref(),
+                &payload,
+            )
+            .await
+            .map(Translation::from)
+        })
+    }
+
+    fn translate_stream<'a>(
+        &'a self,
+        client: &'a Client,
+        settings: &'a AppSettings,
+        request: &'a TranslationRequest,
+        on_delta: DeltaSink<'a>,
+    ) -> EngineFuture<'a, Translation> {
+        Box::pin(async move {
+            let (url, model) = Self::target(settings)?;
+
+            info!(
+                "[openai] Streaming POST {} model: {}, text length: {}",
+                url,
+                model,
+                request.text.len()
+            );
+
+            let mut payload =
+                ChatCompletionRequest::for_translation(model, &settings.glossary, request);
+            stream_chat_completion(
+                client,
+                settings,
+                self.id(),
+                &url,
+                settings.openai_api_key.as_deref(),
+                &mut payload,
+                on_delta,
+            )
+            .await
+            .map(Translation::from)
+        })
+    }
+}
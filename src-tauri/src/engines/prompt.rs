@@ -0,0 +1,67 @@
+//! Prompt building shared by the LLM-backed engines.
+
+use super::TranslationRequest;
+use crate::glossary::{self, GlossaryEntry};
+use crate::languages;
+
+pub fn tone_instruction(tone: &str) -> &'static str {
+    match tone {
+        "Formal" => "Use a professional, formal, and polite tone suitable for business contexts.",
+        "Casual" => "Use a casual, natural, and conversational tone as used in daily life.",
+        "Academic" => "Use an academic, rigorous, and objective tone with appropriate terminology.",
+        "Creative" => {
+            "Use a creative, vivid, and expressive tone with literary devices if appropriate."
+        }
+        _ => "Use a natural and fluent tone.",
+    }
+}
+
+/// System prompt for `request`, with the glossary terms that occur in its text
+pub fn build_system_prompt(glossary: &[GlossaryEntry], request: &TranslationRequest) -> String {
+    let target = languages::prompt_name(&request.target_lang);
+    let tone_instruction = tone_instruction(&request.tone);
+    // A known source matters for short texts that are valid in several
+    // languages, e.g. kanji that read as Chinese
+    let direction = match request.source_lang.as_deref() {
+        Some(source_lang) => {
+            let source = languages::prompt_name(source_lang);
+            format!("from {source} into {target}. Treat the text as {source} even if it could be read as another language.")
+        }
+        None => format!("into {target}."),
+    };
+    let prompt = format!(
+        "You are a professional translation engine. Translate the provided text {direction} {tone_instruction} Requirements: Output ONLY the translated text without explanations, quotes, Markdown, numbering, or extra content. Preserve original line breaks and formatting as much as possible.",
+    );
+    match glossary_instruction(&glossary::matching_terms(glossary, request)) {
+        Some(instruction) => format!("{prompt}\n\n{instruction}"),
+        None => prompt,
+    }
+}
+
+/// Prompt section listing glossary terms the model must use verbatim
+fn glossary_instruction(terms: &[&GlossaryEntry]) -> Option<String> {
+    if terms.is_empty() {
+        return None;
+    }
+    let lines: Vec<String> = terms
+        .iter()
+        .map(|term| format!("- {} => {}", term.source_term, term.target_term))
+        .collect();
+    Some(format!(
+        "Glossary: always translate the following terms exactly as given, keeping the target term unchanged:\n{}",
+        lines.join("\n")
+    ))
+}
+
+pub fn strip_code_fences(text: &str) -> String {
+    let trimmed = text.trim();
+    if trimmed.starts_with("```") && trimmed.ends_with("```") {
+        let without_start = trimmed.trim_start_matches("```");
+        let without_lang = without_start
+            .trim_start_matches(|c: char| c.is_ascii_alphanumeric() || c == '-')
+            .trim_start_matches(['\n', '\r', ' ']);
+        let without_end = without_lang.trim_end_matches("```").trim();
+        return without_end.to_string();
+    }
+    trimmed.to_string()
+}
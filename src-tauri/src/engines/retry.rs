@@ -0,0 +1,231 @@
+//! Retries with exponential backoff for engine HTTP calls.
+//!
+//! Connection errors and 408/429/5xx responses are retried by [`send`].
+//! Providers that report errors inside a 200 response, e.g. Tencent's
+//! `RequestLimitExceeded`, go through [`with_retry`], which retries the
+//! errors their codes map to [retryable](AppError::is_retryable) ones. Their
+//! transport errors go through [`AppError::transport`], so as in [`send`]
+//! only failures to connect are retried.
+//! A `Retry-After` header replaces the backoff delay when present.
+
+use std::future::Future;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use log::warn;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{RequestBuilder, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::AppSettings;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt; 0 disables retrying
+    pub max_retries: u32,
+    /// Wait before the first retry, doubled for each further one
+    pub base_delay_ms: u64,
+    /// Longest single wait. A longer `Retry-After` fails right away rather
+    /// than leaving the translation hanging.
+    pub max_delay_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            base_delay_ms: 500,
+            max_delay_ms: 8_000,
+        }
+    }
+}
+
+impl RetryPolicy {
+    fn max_delay(&self) -> Duration {
+        Duration::from_millis(self.max_delay_ms)
+    }
+
+    /// Wait before retry number `retry`, counting from 0
+    fn backoff(&self, retry: u32) -> Duration {
+        let delay = self.base_delay_ms.saturating_mul(1 << retry.min(16));
+        Duration::from_millis(delay).min(self.max_delay())
+    }
+}
+
+/// The policy for `engine`: its override if one is set, else the default
+fn policy(settings: &AppSettings, engine: &str) -> RetryPolicy {
+    settings
+        .engine_retry_policies
+        .get(engine)
+        .copied()
+        .unwrap_or(settings.retry_policy)
+}
+
+/// 529 is Anthropic's "overloaded"
+fn is_retryable_status(status: StatusCode) -> bool {
+    matches!(status.as_u16(), 408 | 429 | 500 | 502 | 503 | 504 | 529)
+}
+
+/// Only failures to connect, where the request never reached the engine.
+/// A timeout may come after the engine started working, and sending a billed
+/// completion again would charge for it twice.
+fn is_retryable_transport(error: &reqwest::Error) -> bool {
+    error.is_connect()
+}
+
+/// `Retry-After` as either delay seconds or an HTTP date
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (date.with_timezone(&Utc) - Utc::now())
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
+
+/// Send `request`, retrying according to the engine's [`RetryPolicy`].
+///
+/// The last response is returned whatever its status so engines keep their
+/// own error messages. Requests whose body can't be cloned are sent once.
+pub async fn send(
+    settings: &AppSettings,
+    engine: &str,
+    request: RequestBuilder,
+) -> Result<Response, reqwest::Error> {
+    let policy = policy(settings, engine);
+    let mut retry = 0;
+    loop {
+        let attempt = match request.try_clone() {
+            Some(attempt) if retry < policy.max_retries => attempt,
+            _ => return request.send().await,
+        };
+
+        let (delay, reason) = match attempt.send().await {
+            Ok(res) if is_retryable_status(res.status()) => {
+                let delay = match retry_after(res.headers()) {
+                    Some(delay) if delay > policy.max_delay() => return Ok(res),
+                    Some(delay) => delay,
+                    None => policy.backoff(retry),
+                };
+                (delay, format!("HTTP {}", res.status()))
+            }
+            Err(e) if is_retryable_transport(&e) => (policy.backoff(retry), e.to_string()),
+            result => return result,
+        };
+
+        retry += 1;
+        warn!(
+            "[retry] '{}' attempt {} failed ({}), retrying in {:?}",
+            engine, retry, reason, delay
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Run `attempt` until it succeeds, fails with an error that isn't
+/// [retryable](AppError::is_retryable), or the policy runs out of retries.
+pub async fn with_retry<T, F, Fut>(
+    settings: &AppSettings,
+    engine: &str,
+    mut attempt: F,
+) -> Result<T, AppError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, AppError>>,
+{
+    let policy = policy(settings, engine);
+    let mut retry = 0;
+    loop {
+        let error = match attempt().await {
+            Ok(value) => return Ok(value),
+            Err(error) => error,
+        };
+        if !error.is_retryable() || retry >= policy.max_retries {
+            return Err(error);
+        }
+
+        let delay = policy.backoff(retry);
+        retry += 1;
+        warn!(
+            "[retry] '{}' attempt {} failed ({}), retrying in {:?}",
+            engine, retry, error.details, delay
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use tokio::io::AsyncReadExt;
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::error::ErrorCode;
+
+    fn settings() -> AppSettings {
+        AppSettings {
+            retry_policy: RetryPolicy {
+                max_retries: 2,
+                base_delay_ms: 0,
+                max_delay_ms: 0,
+            },
+            ..AppSettings::default()
+        }
+    }
+
+    /// POST to `url` the way Tencent, Baidu and Youdao do, counting attempts
+    async fn post_with_retry(url: &str, attempts: &AtomicU32) -> Result<(), AppError> {
+        let client = reqwest::Client::new();
+        with_retry(&settings(), "test", || async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            client
+                .post(url)
+                .body("q=hello")
+                .send()
+                .await
+                .map(|_| ())
+                .map_err(|e| AppError::transport(format!("Request failed: {}", e), &e))
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn connect_failure_is_retried() {
+        // Nothing listens on the port once the listener is dropped
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        let attempts = AtomicU32::new(0);
+        let error = post_with_retry(&url, &attempts).await.unwrap_err();
+        assert_eq!(error.code, ErrorCode::Network);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn failure_after_send_is_not_retried() {
+        // Read the whole request, then close the connection without answering
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = [0; 1024];
+                let _ = socket.read(&mut buf).await;
+            }
+        });
+
+        let attempts = AtomicU32::new(0);
+        let error = post_with_retry(&url, &attempts).await.unwrap_err();
+        assert_eq!(error.code, ErrorCode::ConnectionLost);
+        assert!(!error.is_retryable());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,277 @@
+//! Youdao Zhiyun (有道智云) engine with v3 SHA-256 signing.
+//!
+//! Single-word queries also return Youdao's dictionary data (phonetics and
+//! short explanations), which is passed through as a [`DictionaryEntry`].
+
+use chrono::Utc;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::retry;
+use super::{
+    DictionaryEntry, EngineCapabilities, EngineFuture, EngineKind, Translation, TranslationEngine,
+    TranslationRequest,
+};
+use crate::error::{AppError, ErrorCode};
+use crate::languages::{self, CodeTable, Language};
+use crate::AppSettings;
+
+const YOUDAO_TRANSLATE_URL: &str = "https://openapi.youdao.com/api";
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct YoudaoRequest<'a> {
+    q: &'a str,
+    from: &'a str,
+    to: &'a str,
+    app_key: &'a str,
+    salt: &'a str,
+    sign: &'a str,
+    sign_type: &'a str,
+    curtime: &'a str,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct YoudaoResponse {
+    error_code: String,
+    translation: Option<Vec<String>>,
+    basic: Option<YoudaoBasic>,
+    #[serde(default)]
+    is_word: bool,
+}
+
+#[derive(Deserialize)]
+struct YoudaoBasic {
+    phonetic: Option<String>,
+    #[serde(rename = "us-phonetic")]
+    us_phonetic: Option<String>,
+    #[serde(rename = "uk-phonetic")]
+    uk_phonetic: Option<String>,
+    #[serde(default)]
+    explains: Vec<String>,
+}
+
+pub struct YoudaoEngine;
+
+impl TranslationEngine for YoudaoEngine {
+    fn id(&self) -> &'static str {
+        "youdao"
+    }
+
+    fn capabilities(&self) -> EngineCapabilities {
+        EngineCapabilities {
+            id: self.id(),
+            name: "Youdao",
+            kind: EngineKind::Machine,
+            supports_tone: false,
+            supports_streaming: false,
+        }
+    }
+
+    fn validate_config(&self, settings: &AppSettings) -> Result<(), AppError> {
+        if settings.youdao_app_key.is_none() {
+            return Err(AppError::invalid_config("未配置有道智云应用 ID"));
+        }
+        if settings.youdao_app_secret.is_none() {
+            return Err(AppError::invalid_config("未配置有道智云应用密钥"));
+        }
+        Ok(())
+    }
+
+    fn supported_languages(&self) -> Vec<Language> {
+        languages::supported_by(LANGUAGE_CODES)
+    }
+
+    fn translate<'a>(
+        &'a self,
+        client: &'a Client,
+        settings: &'a AppSettings,
+        request: &'a TranslationRequest,
+    ) -> EngineFuture<'a, Translation> {
+        Box::pin(translate_youdao(client, settings, request))
+    }
+}
+
+/// Youdao's `input`: the text itself when it has at most 20 characters,
+/// otherwise the first 10 characters + character count + last 10 characters.
+fn truncate_input(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let len = chars.len();
+    if len <= 20 {
+        return text.to_string();
+    }
+    let head: String = chars[..10].iter().collect();
+    let tail: String = chars[len - 10..].iter().collect();
+    format!("{}{}{}", head, len, tail)
+}
+
+/// sign = SHA256(appKey + input + salt + curtime + appSecret), lowercase hex
+fn youdao_sign(app_key: &str, text: &str, salt: &str, curtime: &str, app_secret: &str) -> String {
+    let sign_str = format!(
+        "{}{}{}{}{}",
+        app_key,
+        truncate_input(text),
+        salt,
+        curtime,
+        app_secret
+    );
+    hex::encode(Sha256::digest(sign_str.as_bytes()))
+}
+
+const LANGUAGE_CODES: CodeTable = &[
+    ("zh", "zh-CHS"),
+    ("zh-Hant", "zh-CHT"),
+    ("en", "en"),
+    ("ja", "ja"),
+    ("ko", "ko"),
+    ("fr", "fr"),
+    ("de", "de"),
+    ("es", "es"),
+    ("pt", "pt"),
+    ("it", "it"),
+    ("ru", "ru"),
+    ("ar", "ar"),
+    ("th", "th"),
+    ("vi", "vi"),
+    ("id", "id"),
+    ("tr", "tr"),
+    ("nl", "nl"),
+    ("pl", "pl"),
+];
+
+fn youdao_lang(lang: &str) -> &str {
+    languages::engine_code(LANGUAGE_CODES, lang)
+}
+
+/// Map a Youdao `errorCode`, see the API's error code table
+fn error_code(code: &str) -> ErrorCode {
+    match code {
+        // Invalid app key, no instance bound, account disabled, bad signature or time
+        "108" | "110" | "111" | "202" | "206" => ErrorCode::Auth,
+        "401" => ErrorCode::Quota,
+        // Rate limited, too many long requests
+        "411" | "412" => ErrorCode::RateLimited,
+        _ => ErrorCode::EngineRejected,
+    }
+}
+
+async fn translate_youdao(
+    client: &Client,
+    settings: &AppSettings,
+    request: &TranslationRequest,
+) -> Result<Translation, AppError> {
+    let app_key = settings
+        .youdao_app_key
+        .as_deref()
+        .ok_or_else(|| AppError::invalid_config("未配置有道智云应用 ID"))?;
+    let app_secret = settings
+        .youdao_app_secret
+        .as_deref()
+        .ok_or_else(|| AppError::invalid_config("未配置有道智云应用密钥"))?;
+
+    let target = youdao_lang(&request.target_lang);
+    let source = request.source_lang.as_deref().map_or("auto", youdao_lang);
+
+    let now = Utc::now();
+    let salt = now.timestamp_millis().to_string();
+    let curtime = now.timestamp().to_string();
+    let sign = youdao_sign(app_key, &request.text, &salt, &curtime, app_secret);
+
+    let payload = YoudaoRequest {
+        q: &request.text,
+        from: source,
+        to: target,
+        app_key,
+        salt: &salt,
+        sign: &sign,
+        sign_type: "v3",
+        curtime: &curtime,
+    };
+
+    let resp_data: YoudaoResponse = retry::with_retry(settings, "youdao", || async {
+        let res = client
+            .post(YOUDAO_TRANSLATE_URL)
+            .form(&payload)
+            .send()
+            .await
+            .map_err(|e| AppError::transport(format!("Request failed: {}", e), &e))?;
+
+        let resp_data: YoudaoResponse = res
+            .json()
+            .await
+            .map_err(|e| AppError::invalid_response(format!("Parse error: {}", e)))?;
+
+        match resp_data.error_code.as_str() {
+            "0" => Ok(resp_data),
+            code => Err(AppError::new(
+                error_code(code),
+                format!("Youdao API Error ({})", code),
+            )),
+        }
+    })
+    .await?;
+
+    let text = resp_data.translation.unwrap_or_default().join("\n");
+    if text.is_empty() {
+        return Err(AppError::invalid_response("有道翻译未返回结果"));
+    }
+
+    let dictionary = resp_data
+        .basic
+        .filter(|_| resp_data.is_word)
+        .map(|basic| DictionaryEntry {
+            phonetic: basic.phonetic,
+            us_phonetic: basic.us_phonetic,
+            uk_phonetic: basic.uk_phonetic,
+            explains: basic.explains,
+        });
+
+    Ok(Translation {
+        dictionary,
+        ..Translation::from(text)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncate_input_keeps_short_text() {
+        assert_eq!(truncate_input("Hello"), "Hello");
+        assert_eq!(
+            truncate_input("abcdefghijklmnopqrst"),
+            "abcdefghijklmnopqrst"
+        );
+    }
+
+    #[test]
+    fn truncate_input_shortens_long_text() {
+        // Example from Youdao's signing docs
+        assert_eq!(
+            truncate_input("Welcome to youdao AICloud."),
+            "Welcome to26o AICloud."
+        );
+        // Counted in characters, not bytes
+        assert_eq!(
+            truncate_input("这是一段超过二十个字符的中文文本，用来测试有道的截断规则"),
+            "这是一段超过二十个字28来测试有道的截断规则"
+        );
+    }
+
+    #[test]
+    fn sign_hashes_truncated_input() {
+        assert_eq!(
+            youdao_sign(
+                "appKey",
+                "这是一段超过二十个字符的中文文本，用来测试有道的截断规则",
+                "salt",
+                "1700000000",
+                "secret"
+            ),
+            "aa654ef94aad247a23afbc17dc0137daa3ee692adf0242244ca044f5a2987540"
+        );
+    }
+}
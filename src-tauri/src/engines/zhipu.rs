@@ -0,0 +1,128 @@
+//! Zhipu AI (GLM) chat-completions engine.
+
+use log::{error, info};
+use reqwest::Client;
+
+use super::chat::{
+    send_chat_completion, stream_chat_completion, ChatCompletionRequest, ChatResponseFormat,
+};
+use super::{
+    DeltaSink, EngineCapabilities, EngineFuture, EngineKind, Translation, TranslationEngine,
+    TranslationRequest,
+};
+use crate::error::AppError;
+use crate::AppSettings;
+
+const ZHIPU_CHAT_URL: &str = "https://open.bigmodel.cn/api/paas/v4/chat/completions";
+
+pub struct ZhipuEngine;
+
+impl ZhipuEngine {
+    fn api_key(settings: &AppSettings) -> Option<String> {
+        settings
+            .zhipu_api_key
+            .clone()
+            .or_else(|| std::env::var("ZHIPU_API_KEY").ok())
+    }
+
+    fn payload(settings: &AppSettings, request: &TranslationRequest) -> ChatCompletionRequest {
+        let mut payload =
+            ChatCompletionRequest::for_translation("glm-4.6", &settings.glossary, request);
+        payload.do_sample = Some(false);
+        payload.response_format = Some(ChatResponseFormat {
+            kind: "text".to_string(),
+        });
+        payload
+    }
+
+    fn require_api_key(settings: &AppSettings) -> Result<String, AppError> {
+        Self::api_key(settings).ok_or_else(|| {
+            error!("[zhipu] No API key configured");
+            AppError::invalid_config("未配置智谱 AI API Key：请在设置页配置")
+        })
+    }
+}
+
+impl TranslationEngine for ZhipuEngine {
+    fn id(&self) -> &'static str {
+        "zhipu"
+    }
+
+    fn capabilities(&self) -> EngineCapabilities {
+        EngineCapabilities {
+            id: self.id(),
+            name: "Zhipu AI",
+            kind: EngineKind::Llm,
+            supports_tone: true,
+            supports_streaming: true,
+        }
+    }
+
+    fn validate_config(&self, settings: &AppSettings) -> Result<(), AppError> {
+        Self::api_key(settings)
+            .map(|_| ())
+            .ok_or_else(|| AppError::invalid_config("未配置智谱 AI API Key：请在设置页配置"))
+    }
+
+    fn translate<'a>(
+        &'a self,
+        client: &'a Client,
+        settings: &'a AppSettings,
+        request: &'a TranslationRequest,
+    ) -> EngineFuture<'a, Translation> {
+        Box::pin(async move {
+            info!(
+                "[zhipu] Text length: {}, target: {}, tone: {}",
+                request.text.len(),
+                request.target_lang,
+                request.tone
+            );
+
+            let api_key = Self::require_api_key(settings)?;
+            let payload = Self::payload(settings, request);
+
+            send_chat_completion(
+                client,
+                settings,
+                self.id(),
+                ZHIPU_CHAT_URL,
+                Some(&api_key),
+                &payload,
+            )
+            .await
+            .map(Translation::from)
+        })
+    }
+
+    fn translate_stream<'a>(
+        &'a self,
+        client: &'a Client,
+        settings: &'a AppSettings,
+        request: &'a TranslationRequest,
+        on_delta: DeltaSink<'a>,
+    ) -> EngineFuture<'a, Translation> {
+        Box::pin(async move {
+            info!(
+                "[zhipu] Streaming. Text length: {}, target: {}, tone: {}",
+                request.text.len(),
+                request.target_lang,
+                request.tone
+            );
+
+            let api_key = Self::require_api_key(settings)?;
+            let mut payload = Self::payload(settings, request);
+
+            stream_chat_completion(
+                client,
+                settings,
+                self.id(),
+                ZHIPU_CHAT_URL,
+                Some(&api_key),
+                &mut payload,
+                on_delta,
+            )
+            .await
+            .map(Translation::from)
+        })
+    }
+}
@@ -0,0 +1,164 @@
+//! Errors returned by the commands.
+//!
+//! Serialized as `{ code, engine, details }`: the frontend picks a localized
+//! message by `code` and decides from it whether to offer a retry or open the
+//! settings. `details` is the raw message, kept for logs and tooltips.
+
+use std::fmt;
+
+use reqwest::StatusCode;
+use serde::Serialize;
+
+/// Stable error codes, serialized in snake_case
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// The request never reached the engine: DNS or connection failures
+    Network,
+    /// The connection broke after the request was sent; the engine may have
+    /// processed it
+    ConnectionLost,
+    /// The engine didn't answer in time; it may have processed the request
+    Timeout,
+    /// The engine rejected the credentials
+    Auth,
+    /// Character or billing quota used up
+    Quota,
+    /// Too many requests for now
+    RateLimited,
+    /// A setting the operation needs is missing or invalid
+    InvalidConfig,
+    /// The engine refused the request itself, e.g. an unsupported language
+    EngineRejected,
+    /// The engine's safety filters refused the content
+    ContentBlocked,
+    /// The engine failed on its side
+    EngineUnavailable,
+    /// The engine answered with something that couldn't be read
+    InvalidResponse,
+    /// The input itself can't be handled, e.g. text in no recognizable language
+    UnsupportedInput,
+    Cancelled,
+    Clipboard,
+    Window,
+    /// Settings, cache, history or phrasebook storage failed
+    Storage,
+    /// A history entry or phrase that no longer exists
+    NotFound,
+    Internal,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AppError {
+    pub code: ErrorCode,
+    /// Engine the error came from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub engine: Option<String>,
+    pub details: String,
+}
+
+impl AppError {
+    pub fn new(code: ErrorCode, details: impl Into<String>) -> Self {
+        Self {
+            code,
+            engine: None,
+            details: details.into(),
+        }
+    }
+
+    pub fn invalid_config(details: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidConfig, details)
+    }
+
+    pub fn rejected(details: impl Into<String>) -> Self {
+        Self::new(ErrorCode::EngineRejected, details)
+    }
+
+    pub fn invalid_response(details: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidResponse, details)
+    }
+
+    pub fn storage(details: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Storage, details)
+    }
+
+    pub fn clipboard(details: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Clipboard, details)
+    }
+
+    pub fn window(details: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Window, details)
+    }
+
+    /// A request that got no response. A URL that doesn't parse comes from
+    /// the settings rather than the network. Only a failure to connect is
+    /// `Network`; anything later may have reached the engine.
+    pub fn transport(details: impl Into<String>, error: &reqwest::Error) -> Self {
+        let code = if error.is_builder() {
+            ErrorCode::InvalidConfig
+        } else if error.is_timeout() {
+            ErrorCode::Timeout
+        } else if error.is_connect() {
+            ErrorCode::Network
+        } else {
+            ErrorCode::ConnectionLost
+        };
+        Self::new(code, details)
+    }
+
+    /// Classify an unsuccessful HTTP response
+    pub fn from_status(status: StatusCode, details: impl Into<String>) -> Self {
+        let code = match status.as_u16() {
+            401 | 403 => ErrorCode::Auth,
+            402 => ErrorCode::Quota,
+            408 => ErrorCode::Network,
+            429 => ErrorCode::RateLimited,
+            500..=599 => ErrorCode::EngineUnavailable,
+            _ => ErrorCode::EngineRejected,
+        };
+        Self::new(code, details)
+    }
+
+    /// Attribute the error to `engine` unless it already names one
+    pub fn with_engine(mut self, engine: &str) -> Self {
+        self.engine.get_or_insert_with(|| engine.to_string());
+        self
+    }
+
+    /// Whether the same request may succeed if sent again shortly. Timeouts
+    /// and lost connections are left out, as resending could bill the request
+    /// twice.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self.code,
+            ErrorCode::Network | ErrorCode::RateLimited | ErrorCode::EngineUnavailable
+        )
+    }
+
+    /// Whether another engine may succeed where this one failed. Rejected
+    /// input and unreadable answers are not worth a second engine.
+    pub fn should_fall_back(&self) -> bool {
+        matches!(
+            self.code,
+            ErrorCode::Network
+                | ErrorCode::ConnectionLost
+                | ErrorCode::Timeout
+                | ErrorCode::Auth
+                | ErrorCode::Quota
+                | ErrorCode::RateLimited
+                | ErrorCode::InvalidConfig
+                | ErrorCode::EngineUnavailable
+        )
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.engine {
+            Some(engine) => write!(f, "[{}] {}", engine, self.details),
+            None => f.write_str(&self.details),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
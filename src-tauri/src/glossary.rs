@@ -0,0 +1,175 @@
+//! User glossary: fixed translations for product names and domain terms.
+//!
+//! Entries are stored in `AppSettings::glossary`. Engines that accept
+//! instructions get the matching terms in their prompt; every result is then
+//! checked with [`verify`] so the UI can flag terms the engine ignored.
+
+use serde::{Deserialize, Serialize};
+
+use crate::engines::TranslationRequest;
+use crate::languages::{is_cjk, same_language};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GlossaryEntry {
+    pub source_term: String,
+    pub target_term: String,
+    /// Language of `source_term`; `None` applies to any source language
+    #[serde(default)]
+    pub source_lang: Option<String>,
+    pub target_lang: String,
+}
+
+/// A glossary term found in the source text but missing from the output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlossaryViolation {
+    pub source_term: String,
+    pub expected: String,
+}
+
+/// Letters and digits of scripts that separate words with spaces
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() && !is_cjk(c)
+}
+
+/// Case-insensitive search for a whole term: a term that starts or ends with
+/// a word character must not continue a word there, so "AI" doesn't match
+/// "said". CJK terms match anywhere, as CJK text has no word separators.
+fn contains_term(haystack: &str, term: &str) -> bool {
+    let haystack = haystack.to_lowercase();
+    let term = term.to_lowercase();
+    let (Some(first), Some(last)) = (term.chars().next(), term.chars().next_back()) else {
+        return false;
+    };
+
+    haystack.match_indices(&term).any(|(start, _)| {
+        let before = haystack[..start].chars().next_back();
+        let after = haystack[start + term.len()..].chars().next();
+        let separated = |edge: char, neighbour: Option<char>| {
+            !is_word_char(edge) || !neighbour.is_some_and(is_word_char)
+        };
+        separated(first, before) && separated(last, after)
+    })
+}
+
+/// Trim entries and drop incomplete ones
+pub fn normalize(entries: Vec<GlossaryEntry>) -> Vec<GlossaryEntry> {
+    entries
+        .into_iter()
+        .filter_map(|entry| {
+            let source_term = entry.source_term.trim();
+            let target_term = entry.target_term.trim();
+            let target_lang = entry.target_lang.trim();
+            if source_term.is_empty() || target_term.is_empty() || target_lang.is_empty() {
+                return None;
+            }
+            Some(GlossaryEntry {
+                source_term: source_term.to_string(),
+                target_term: target_term.to_string(),
+                source_lang: entry
+                    .source_lang
+                    .map(|lang| lang.trim().to_string())
+                    .filter(|lang| !lang.is_empty()),
+                target_lang: target_lang.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Entries for the request's language pair whose term occurs in the text.
+///
+/// Entries bound to a source language also apply when the request leaves the
+/// source to detection.
+pub fn matching_terms<'a>(
+    entries: &'a [GlossaryEntry],
+    request: &TranslationRequest,
+) -> Vec<&'a GlossaryEntry> {
+    entries
+        .iter()
+        .filter(|entry| same_language(&entry.target_lang, &request.target_lang))
+        .filter(|entry| match (&entry.source_lang, &request.source_lang) {
+            (Some(entry_source), Some(request_source)) => {
+                same_language(entry_source, request_source)
+            }
+            _ => true,
+        })
+        .filter(|entry| contains_term(&request.text, &entry.source_term))
+        .collect()
+}
+
+/// Terms from `entries` that apply to the request but are absent from `output`
+pub fn verify(
+    entries: &[GlossaryEntry],
+    request: &TranslationRequest,
+    output: &str,
+) -> Vec<GlossaryViolation> {
+    matching_terms(entries, request)
+        .into_iter()
+        .filter(|entry| !contains_term(output, &entry.target_term))
+        .map(|entry| GlossaryViolation {
+            source_term: entry.source_term.clone(),
+            expected: entry.target_term.clone(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(source_term: &str, target_term: &str, target_lang: &str) -> GlossaryEntry {
+        GlossaryEntry {
+            source_term: source_term.to_string(),
+            target_term: target_term.to_string(),
+            source_lang: None,
+            target_lang: target_lang.to_string(),
+        }
+    }
+
+    fn request(text: &str, target_lang: &str) -> TranslationRequest {
+        TranslationRequest {
+            text: text.to_string(),
+            source_lang: None,
+            target_lang: target_lang.to_string(),
+            tone: String::new(),
+        }
+    }
+
+    #[test]
+    fn contains_term_matches_whole_words_only() {
+        assert!(contains_term("the cat sat", "cat"));
+        assert!(contains_term("cat.", "cat"));
+        assert!(!contains_term("concatenate", "cat"));
+        assert!(!contains_term("he said so", "AI"));
+        assert!(contains_term("C++ rocks", "C++"));
+    }
+
+    #[test]
+    fn contains_term_ignores_case() {
+        assert!(contains_term("Use OpenAI today", "openai"));
+        assert!(contains_term("use openai today", "OpenAI"));
+    }
+
+    #[test]
+    fn contains_term_matches_cjk_inside_text() {
+        assert!(contains_term("我们使用人工智能翻译", "人工智能"));
+        assert!(contains_term("新しい翻訳エンジン", "翻訳"));
+        assert!(!contains_term("我们使用机器翻译", "人工智能"));
+    }
+
+    #[test]
+    fn verify_reports_missing_target_terms() {
+        let entries = [
+            entry("Tauri", "Tauri", "zh"),
+            entry("glossary", "术语表", "zh"),
+            entry("cache", "Cache", "ja"),
+        ];
+        let request = request("Tauri keeps a glossary and a cache", "zh");
+
+        let violations = verify(&entries, &request, "Tauri 维护了一份词汇表和缓存");
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].source_term, "glossary");
+        assert_eq!(violations[0].expected, "术语表");
+
+        assert!(verify(&entries, &request, "Tauri 维护了一份术语表和缓存").is_empty());
+    }
+}
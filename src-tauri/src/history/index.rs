@@ -0,0 +1,132 @@
+//! Terms for the `history_fts` full-text index.
+//!
+//! SQLite's `unicode61` tokenizer splits on whitespace and punctuation only,
+//! so a run of Chinese or Japanese text would become one huge token. Before
+//! indexing, CJK runs are rewritten into their single characters plus
+//! overlapping bigrams, which lets a query for any word inside the run match.
+
+use crate::languages::is_cjk;
+
+/// Split `text` into lowercase non-CJK words and CJK runs
+fn segments(text: &str) -> Vec<(bool, String)> {
+    let mut segments: Vec<(bool, String)> = Vec::new();
+    for c in text.chars() {
+        let cjk = is_cjk(c);
+        if !cjk && !c.is_alphanumeric() {
+            // Separator; starts a new segment on the next word character
+            segments.push((false, String::new()));
+            continue;
+        }
+        match segments.last_mut() {
+            Some((last_cjk, segment)) if *last_cjk == cjk => segment.extend(c.to_lowercase()),
+            _ => segments.push((cjk, c.to_lowercase().collect())),
+        }
+    }
+    segments.retain(|(_, segment)| !segment.is_empty());
+    segments
+}
+
+fn bigrams(chars: &[char]) -> impl Iterator<Item = String> + '_ {
+    chars.windows(2).map(|pair| pair.iter().collect())
+}
+
+/// Space separated terms stored in the index for `text`
+pub fn index_terms(text: &str) -> String {
+    let mut terms: Vec<String> = Vec::new();
+    for (cjk, segment) in segments(text) {
+        if cjk {
+            let chars: Vec<char> = segment.chars().collect();
+            terms.extend(chars.iter().map(|c| c.to_string()));
+            terms.extend(bigrams(&chars));
+        } else {
+            terms.push(segment);
+        }
+    }
+    terms.join(" ")
+}
+
+/// FTS5 `MATCH` expression requiring every term of `query`, or `None` if the
+/// query has no searchable characters.
+///
+/// CJK runs are matched by their bigrams (or the single character), and the
+/// last Latin word is matched as a prefix so results show up while typing.
+pub fn match_query(query: &str) -> Option<String> {
+    let segments = segments(query);
+    let last = segments.len().checked_sub(1)?;
+
+    let mut terms: Vec<String> = Vec::new();
+    for (i, (cjk, segment)) in segments.into_iter().enumerate() {
+        if cjk {
+            let chars: Vec<char> = segment.chars().collect();
+            if chars.len() == 1 {
+                terms.push(quote(&segment));
+            } else {
+                terms.extend(bigrams(&chars).map(|term| quote(&term)));
+            }
+        } else if i == last {
+            terms.push(format!("{}*", quote(&segment)));
+        } else {
+            terms.push(quote(&segment));
+        }
+    }
+    Some(terms.join(" AND "))
+}
+
+fn quote(term: &str) -> String {
+    format!("\"{}\"", term.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_terms_lowercases_ascii_words() {
+        assert_eq!(index_terms("Hello, World! v2"), "hello world v2");
+    }
+
+    #[test]
+    fn index_terms_splits_cjk_into_characters_and_bigrams() {
+        assert_eq!(index_terms("翻译历史"), "翻 译 历 史 翻译 译历 历史");
+    }
+
+    #[test]
+    fn index_terms_separates_mixed_runs() {
+        assert_eq!(index_terms("用Rust写"), "用 rust 写");
+        assert_eq!(index_terms("AI翻译"), "ai 翻 译 翻译");
+    }
+
+    #[test]
+    fn match_query_prefixes_last_ascii_word() {
+        assert_eq!(
+            match_query("hello Wor").as_deref(),
+            Some(r#""hello" AND "wor"*"#)
+        );
+    }
+
+    #[test]
+    fn match_query_uses_cjk_bigrams() {
+        assert_eq!(
+            match_query("翻译历史").as_deref(),
+            Some(r#""翻译" AND "译历" AND "历史""#)
+        );
+        assert_eq!(match_query("译").as_deref(), Some(r#""译""#));
+    }
+
+    #[test]
+    fn match_query_handles_mixed_input() {
+        assert_eq!(
+            match_query("AI翻译 model").as_deref(),
+            Some(r#""ai" AND "翻译" AND "model"*"#)
+        );
+    }
+
+    #[test]
+    fn match_query_ignores_punctuation_only_input() {
+        assert_eq!(match_query("  ?! "), None);
+        assert_eq!(
+            match_query(r#"say "hi"#).as_deref(),
+            Some(r#""say" AND "hi"*"#)
+        );
+    }
+}
@@ -0,0 +1,319 @@
+//! Translation history stored in a SQLite database in the app data dir.
+//!
+//! Source and translated text are also kept in an FTS5 table (`history_fts`)
+//! for ranked search, see [`index`] for how CJK text is tokenized.
+
+mod index;
+
+use std::sync::Mutex;
+
+use chrono::{Duration, Utc};
+use log::info;
+use rusqlite::{params, Connection, Row, Transaction};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::engines::{Translation, TranslationRequest};
+use crate::error::AppError;
+
+const HISTORY_DB: &str = "history.db";
+/// Bumped whenever `migrate` gains a step, stored in `PRAGMA user_version`
+const SCHEMA_VERSION: i32 = 1;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistorySettings {
+    /// When false nothing is written to the history
+    pub enabled: bool,
+    /// Entries older than this are deleted; `None` keeps everything
+    pub retention_days: Option<u32>,
+}
+
+impl Default for HistorySettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            retention_days: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryEntry {
+    pub id: i64,
+    pub source_text: String,
+    pub translated_text: String,
+    pub engine: String,
+    /// `None` when the engine detected the source language
+    pub source_lang: Option<String>,
+    pub target_lang: String,
+    pub tone: String,
+    /// Unix timestamp in seconds
+    pub created_at: i64,
+}
+
+impl HistoryEntry {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get(0)?,
+            source_text: row.get(1)?,
+            translated_text: row.get(2)?,
+            engine: row.get(3)?,
+            source_lang: row.get(4)?,
+            target_lang: row.get(5)?,
+            tone: row.get(6)?,
+            created_at: row.get(7)?,
+        })
+    }
+}
+
+const ENTRY_COLUMNS: &str = "h.id, h.source_text, h.translated_text, h.engine, h.source_lang,
+    h.target_lang, h.tone, h.created_at";
+
+/// Narrows a history search; unset fields match everything
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct HistoryFilter {
+    pub engine: Option<String>,
+    pub source_lang: Option<String>,
+    pub target_lang: Option<String>,
+    /// Unix timestamp in seconds, inclusive
+    pub since: Option<i64>,
+    /// Unix timestamp in seconds, exclusive
+    pub until: Option<i64>,
+}
+
+const FILTER_CLAUSE: &str = "(?1 IS NULL OR h.engine = ?1)
+    AND (?2 IS NULL OR h.source_lang = ?2 COLLATE NOCASE)
+    AND (?3 IS NULL OR h.target_lang = ?3 COLLATE NOCASE)
+    AND (?4 IS NULL OR h.created_at >= ?4)
+    AND (?5 IS NULL OR h.created_at < ?5)";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistorySearchHit {
+    #[serde(flatten)]
+    pub entry: HistoryEntry,
+    /// Relevance, higher is better; 0 when the query was empty
+    pub score: f64,
+}
+
+fn db_error(e: rusqlite::Error) -> AppError {
+    AppError::storage(format!("History database error: {}", e))
+}
+
+pub struct HistoryStore(Mutex<Connection>);
+
+impl HistoryStore {
+    pub fn open(app: &AppHandle) -> Result<Self, AppError> {
+        let dir = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| AppError::storage(format!("Failed to resolve app data dir: {}", e)))?;
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| AppError::storage(format!("Failed to create data dir: {}", e)))?;
+
+        let path = dir.join(HISTORY_DB);
+        info!("[history] Opening {:?}", path);
+        let mut conn = Connection::open(&path).map_err(db_error)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                source_text TEXT NOT NULL,
+                translated_text TEXT NOT NULL,
+                engine TEXT NOT NULL,
+                target_lang TEXT NOT NULL,
+                tone TEXT NOT NULL,
+                created_at INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS history_created_at ON history (created_at);",
+        )
+        .map_err(db_error)?;
+        migrate(&mut conn).map_err(db_error)?;
+
+        Ok(Self(Mutex::new(conn)))
+    }
+
+    /// Save a finished translation and apply the retention period
+    pub fn record(
+        &self,
+        settings: HistorySettings,
+        engine: &str,
+        request: &TranslationRequest,
+        translation: &Translation,
+    ) -> Result<(), AppError> {
+        if !settings.enabled {
+            return Ok(());
+        }
+
+        let mut conn = self.0.lock().unwrap();
+        let tx = conn.transaction().map_err(db_error)?;
+        tx.execute(
+            "INSERT INTO history
+                (source_text, translated_text, engine, source_lang, target_lang, tone, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                request.text,
+                translation.text,
+                engine,
+                request.source_lang,
+                request.target_lang,
+                request.tone,
+                Utc::now().timestamp()
+            ],
+        )
+        .map_err(db_error)?;
+        let id = tx.last_insert_rowid();
+        index_entry(&tx, id, &request.text, &translation.text).map_err(db_error)?;
+
+        prune_expired(&tx, settings.retention_days).map_err(db_error)?;
+        tx.commit().map_err(db_error)
+    }
+
+    /// Delete entries older than the retention period, returning how many
+    pub fn prune(&self, retention_days: Option<u32>) -> Result<usize, AppError> {
+        let conn = self.0.lock().unwrap();
+        prune_expired(&conn, retention_days).map_err(db_error)
+    }
+
+    /// Newest entries first
+    pub fn list(&self, limit: u32, offset: u32) -> Result<Vec<HistoryEntry>, AppError> {
+        let conn = self.0.lock().unwrap();
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {} FROM history h ORDER BY h.created_at DESC, h.id DESC LIMIT ?1 OFFSET ?2",
+                ENTRY_COLUMNS
+            ))
+            .map_err(db_error)?;
+        let rows = stmt
+            .query_map(params![limit, offset], HistoryEntry::from_row)
+            .map_err(db_error)?;
+        rows.collect::<Result<_, _>>().map_err(db_error)
+    }
+
+    /// Entries matching every word of `query`, best match first.
+    ///
+    /// An empty query lists the entries matching `filter`, newest first.
+    pub fn search(
+        &self,
+        query: &str,
+        filter: &HistoryFilter,
+        limit: u32,
+    ) -> Result<Vec<HistorySearchHit>, AppError> {
+        // bm25() is lower for better matches, so negate it for the score
+        let (sql, match_query) = match index::match_query(query) {
+            Some(match_query) => (
+                format!(
+                    "SELECT {}, -bm25(history_fts) FROM history h
+                     JOIN history_fts ON history_fts.rowid = h.id
+                     WHERE history_fts MATCH ?7 AND {}
+                     ORDER BY bm25(history_fts) LIMIT ?6",
+                    ENTRY_COLUMNS, FILTER_CLAUSE
+                ),
+                Some(match_query),
+            ),
+            None => (
+                format!(
+                    "SELECT {}, 0.0 FROM history h
+                     WHERE ?7 IS NULL AND {}
+                     ORDER BY h.created_at DESC, h.id DESC LIMIT ?6",
+                    ENTRY_COLUMNS, FILTER_CLAUSE
+                ),
+                None,
+            ),
+        };
+
+        let conn = self.0.lock().unwrap();
+        let mut stmt = conn.prepare(&sql).map_err(db_error)?;
+        let rows = stmt
+            .query_map(
+                params![
+                    filter.engine,
+                    filter.source_lang,
+                    filter.target_lang,
+                    filter.since,
+                    filter.until,
+                    limit,
+                    match_query
+                ],
+                |row| {
+                    Ok(HistorySearchHit {
+                        entry: HistoryEntry::from_row(row)?,
+                        score: row.get(8)?,
+                    })
+                },
+            )
+            .map_err(db_error)?;
+        rows.collect::<Result<_, _>>().map_err(db_error)
+    }
+
+    pub fn delete(&self, id: i64) -> Result<(), AppError> {
+        let conn = self.0.lock().unwrap();
+        conn.execute("DELETE FROM history WHERE id = ?1", params![id])
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    pub fn clear(&self) -> Result<(), AppError> {
+        let conn = self.0.lock().unwrap();
+        conn.execute("DELETE FROM history", []).map_err(db_error)?;
+        Ok(())
+    }
+}
+
+fn prune_expired(conn: &Connection, retention_days: Option<u32>) -> rusqlite::Result<usize> {
+    match retention_days {
+        Some(days) => {
+            let cutoff = (Utc::now() - Duration::days(days.into())).timestamp();
+            conn.execute("DELETE FROM history WHERE created_at < ?1", params![cutoff])
+        }
+        None => Ok(0),
+    }
+}
+
+fn index_entry(
+    tx: &Transaction,
+    id: i64,
+    source_text: &str,
+    translated_text: &str,
+) -> rusqlite::Result<()> {
+    tx.execute(
+        "INSERT INTO history_fts (rowid, source_terms, translated_terms) VALUES (?1, ?2, ?3)",
+        params![
+            id,
+            index::index_terms(source_text),
+            index::index_terms(translated_text)
+        ],
+    )?;
+    Ok(())
+}
+
+/// Bring a database created by an older version up to [`SCHEMA_VERSION`]
+fn migrate(conn: &mut Connection) -> rusqlite::Result<()> {
+    let version: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version >= SCHEMA_VERSION {
+        return Ok(());
+    }
+
+    let tx = conn.transaction()?;
+    if version < 1 {
+        info!("[history] Building full-text index");
+        tx.execute_batch(
+            "ALTER TABLE history ADD COLUMN source_lang TEXT;
+            CREATE VIRTUAL TABLE history_fts USING fts5(source_terms, translated_terms);
+            CREATE TRIGGER history_fts_delete AFTER DELETE ON history BEGIN
+                DELETE FROM history_fts WHERE rowid = old.id;
+            END;",
+        )?;
+
+        let rows: Vec<(i64, String, String)> = {
+            let mut stmt = tx.prepare("SELECT id, source_text, translated_text FROM history")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+        for (id, source_text, translated_text) in rows {
+            index_entry(&tx, id, &source_text, &translated_text)?;
+        }
+    }
+    tx.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+    tx.commit()
+}
@@ -0,0 +1,153 @@
+//! The HTTP client shared by every engine.
+//!
+//! Built from [`NetworkSettings`] the first time it is needed and rebuilt
+//! whenever the settings change, so proxy, timeout and certificate changes
+//! apply to the next request without a restart.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use log::info;
+use reqwest::{Certificate, Client, NoProxy, Proxy};
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkSettings {
+    /// `http://`, `https://` or `socks5://` proxy for every request
+    pub proxy_url: Option<String>,
+    /// Hosts reached directly despite `proxy_url`, in `NO_PROXY` format, e.g.
+    /// a self-hosted LibreTranslate. Loopback and `NO_PROXY` are always added.
+    pub no_proxy: Vec<String>,
+    /// Ignore the system proxy and `HTTP(S)_PROXY` when no proxy is set
+    pub disable_system_proxy: bool,
+    /// Must be non-zero, as must `read_timeout_secs`
+    pub connect_timeout_secs: u64,
+    /// Longest wait between two reads; streamed responses may take longer
+    /// overall as long as data keeps arriving. Non-streaming LLM calls send
+    /// nothing until the whole translation is done, hence the large default.
+    pub read_timeout_secs: u64,
+    /// PEM files with extra root certificates, e.g. a corporate CA
+    pub extra_root_certificates: Vec<String>,
+}
+
+impl Default for NetworkSettings {
+    fn default() -> Self {
+        Self {
+            proxy_url: None,
+            no_proxy: Vec::new(),
+            disable_system_proxy: false,
+            connect_timeout_secs: 10,
+            read_timeout_secs: 300,
+            extra_root_certificates: Vec::new(),
+        }
+    }
+}
+
+/// Client built for the settings it holds
+#[derive(Default)]
+pub struct HttpClient(Mutex<Option<(NetworkSettings, Client)>>);
+
+impl HttpClient {
+    /// The client for `settings`, rebuilt if they changed since the last call.
+    /// Cloning a client shares its connection pool.
+    pub fn get(&self, settings: &NetworkSettings) -> Result<Client, AppError> {
+        let mut state = self.0.lock().unwrap();
+        if let Some((built_for, client)) = state.as_ref() {
+            if built_for == settings {
+                return Ok(client.clone());
+            }
+        }
+
+        let client = build(settings)?;
+        info!("[http] Built client, proxy: {:?}", settings.proxy_url);
+        *state = Some((settings.clone(), client.clone()));
+        Ok(client)
+    }
+}
+
+/// Hosts that skip the proxy: loopback, so local engines such as Ollama
+/// keep working, then the configured list and `NO_PROXY`
+fn bypass_list(settings: &NetworkSettings) -> String {
+    let env = std::env::var("NO_PROXY")
+        .or_else(|_| std::env::var("no_proxy"))
+        .unwrap_or_default();
+    ["localhost", "127.0.0.0/8", "::1"]
+        .into_iter()
+        .chain(settings.no_proxy.iter().map(String::as_str))
+        .chain(env.split(','))
+        .map(str::trim)
+        .filter(|host| !host.is_empty())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Build a client, failing on a zero timeout or a proxy URL or certificate
+/// file that can't be used
+pub fn build(settings: &NetworkSettings) -> Result<Client, AppError> {
+    // A zero timeout would fail every request before it starts
+    if settings.connect_timeout_secs == 0 || settings.read_timeout_secs == 0 {
+        return Err(AppError::invalid_config(
+            "Connect and read timeouts must be at least one second",
+        ));
+    }
+
+    let mut builder = Client::builder()
+        .connect_timeout(Duration::from_secs(settings.connect_timeout_secs))
+        .read_timeout(Duration::from_secs(settings.read_timeout_secs));
+
+    match settings.proxy_url.as_deref() {
+        Some(url) => {
+            let proxy = Proxy::all(url)
+                .map_err(|e| {
+                    AppError::invalid_config(format!("Invalid proxy URL '{}': {}", url, e))
+                })?
+                .no_proxy(NoProxy::from_string(&bypass_list(settings)));
+            builder = builder.proxy(proxy);
+        }
+        None if settings.disable_system_proxy => builder = builder.no_proxy(),
+        None => {}
+    }
+
+    for path in &settings.extra_root_certificates {
+        let pem = std::fs::read(path).map_err(|e| {
+            AppError::invalid_config(format!("Failed to read certificate '{}': {}", path, e))
+        })?;
+        let certificates = Certificate::from_pem_bundle(&pem).map_err(|e| {
+            AppError::invalid_config(format!("Invalid certificate '{}': {}", path, e))
+        })?;
+        for certificate in certificates {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+
+    builder
+        .build()
+        .map_err(|e| AppError::invalid_config(format!("Failed to build HTTP client: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorCode;
+
+    #[test]
+    fn build_rejects_zero_timeouts() {
+        for settings in [
+            NetworkSettings {
+                connect_timeout_secs: 0,
+                ..NetworkSettings::default()
+            },
+            NetworkSettings {
+                read_timeout_secs: 0,
+                ..NetworkSettings::default()
+            },
+        ] {
+            let error = build(&settings).unwrap_err();
+            assert_eq!(error.code, ErrorCode::InvalidConfig);
+        }
+        assert!(build(&NetworkSettings::default()).is_ok());
+    }
+}
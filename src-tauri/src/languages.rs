@@ -0,0 +1,186 @@
+//! Languages the app offers, identified by BCP-47 codes.
+//!
+//! The frontend and the settings use these codes. Engines translate them to
+//! their own identifiers through a code table, see [`engine_code`].
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Language {
+    /// BCP-47 code, e.g. `zh` or `zh-Hant`
+    pub code: &'static str,
+    /// English name, also used in LLM prompts
+    pub name: &'static str,
+    pub native_name: &'static str,
+}
+
+const fn lang(code: &'static str, name: &'static str, native_name: &'static str) -> Language {
+    Language {
+        code,
+        name,
+        native_name,
+    }
+}
+
+pub static LANGUAGES: &[Language] = &[
+    lang("zh", "Simplified Chinese", "简体中文"),
+    lang("zh-Hant", "Traditional Chinese", "繁體中文"),
+    lang("en", "English", "English"),
+    lang("ja", "Japanese", "日本語"),
+    lang("ko", "Korean", "한국어"),
+    lang("fr", "French", "Français"),
+    lang("de", "German", "Deutsch"),
+    lang("es", "Spanish", "Español"),
+    lang("pt", "Portuguese", "Português"),
+    lang("it", "Italian", "Italiano"),
+    lang("ru", "Russian", "Русский"),
+    lang("ar", "Arabic", "العربية"),
+    lang("th", "Thai", "ไทย"),
+    lang("vi", "Vietnamese", "Tiếng Việt"),
+    lang("id", "Indonesian", "Bahasa Indonesia"),
+    lang("tr", "Turkish", "Türkçe"),
+    lang("nl", "Dutch", "Nederlands"),
+    lang("pl", "Polish", "Polski"),
+];
+
+/// Other spellings accepted for registry codes (compared lowercase)
+const ALIASES: &[(&str, &str)] = &[
+    ("zh-cn", "zh"),
+    ("zh_cn", "zh"),
+    ("zh-hans", "zh"),
+    ("zh-sg", "zh"),
+    ("chinese", "zh"),
+    ("zh-tw", "zh-Hant"),
+    ("zh_tw", "zh-Hant"),
+    ("zh-hk", "zh-Hant"),
+    ("en-us", "en"),
+    ("en_us", "en"),
+    ("en-gb", "en"),
+    ("en_gb", "en"),
+    ("english", "en"),
+    ("jp", "ja"),
+    ("japanese", "ja"),
+    ("kr", "ko"),
+    ("korean", "ko"),
+];
+
+/// Maps registry codes to an engine's own codes
+pub type CodeTable = &'static [(&'static str, &'static str)];
+
+/// Look up a language by code or alias, ignoring case
+pub fn find(code: &str) -> Option<&'static Language> {
+    let code = code.trim().to_ascii_lowercase();
+    let code = ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == code)
+        .map_or(code.as_str(), |(_, canonical)| canonical);
+    LANGUAGES
+        .iter()
+        .find(|lang| lang.code.eq_ignore_ascii_case(code))
+}
+
+/// Script of `lang` from its script subtag, e.g. `latn` for `sr-Latn`.
+/// Chinese always has one: Traditional for Taiwan, Hong Kong and Macau,
+/// Simplified otherwise.
+fn script(lang: &str) -> Option<String> {
+    let mut subtags = lang.trim().split(['-', '_']);
+    let primary = subtags.next()?.to_ascii_lowercase();
+    let mut region = None;
+    for subtag in subtags {
+        match subtag.len() {
+            4 => return Some(subtag.to_ascii_lowercase()),
+            2 => region = Some(subtag.to_ascii_lowercase()),
+            _ => {}
+        }
+    }
+    (primary == "zh").then(|| {
+        let script = match region.as_deref() {
+            Some("tw" | "hk" | "mo") => "hant",
+            _ => "hans",
+        };
+        script.to_string()
+    })
+}
+
+/// Compare languages by their primary subtag, so `EN` matches `en-US`, and
+/// by script where both have one, so `zh-Hant` doesn't match `zh`
+pub fn same_language(a: &str, b: &str) -> bool {
+    let primary = |lang: &str| {
+        lang.trim()
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase()
+    };
+    let same_script = match (script(a), script(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => true,
+    };
+    primary(a) == primary(b) && same_script
+}
+
+/// The engine's code for `lang`. Codes outside the registry or the table are
+/// passed through unchanged so users can still reach any engine language.
+pub fn engine_code(table: CodeTable, lang: &str) -> &str {
+    find(lang)
+        .and_then(|language| table.iter().find(|(code, _)| *code == language.code))
+        .map_or(lang, |(_, engine_code)| engine_code)
+}
+
+/// Registry languages that have an entry in `table`
+pub fn supported_by(table: CodeTable) -> Vec<Language> {
+    LANGUAGES
+        .iter()
+        .filter(|lang| table.iter().any(|(code, _)| *code == lang.code))
+        .copied()
+        .collect()
+}
+
+/// Name to use for `lang` in an LLM prompt
+pub fn prompt_name(lang: &str) -> String {
+    match find(lang) {
+        Some(language) => language.name.to_string(),
+        None => lang.trim().to_string(),
+    }
+}
+
+/// Han, kana and Hangul, the scripts written without spaces between words
+pub fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}'   // Hiragana, Katakana
+        | '\u{3400}'..='\u{4DBF}' // CJK Extension A
+        | '\u{4E00}'..='\u{9FFF}' // CJK Unified Ideographs
+        | '\u{AC00}'..='\u{D7AF}' // Hangul syllables
+        | '\u{F900}'..='\u{FAFF}' // CJK Compatibility Ideographs
+        | '\u{20000}'..='\u{2FA1F}'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_language_ignores_region() {
+        assert!(same_language("EN", "en-US"));
+        assert!(same_language("en_gb", "en"));
+        assert!(!same_language("en", "ja"));
+    }
+
+    #[test]
+    fn same_language_compares_chinese_script() {
+        assert!(same_language("zh", "zh-Hans"));
+        assert!(same_language("zh", "zh-CN"));
+        assert!(same_language("zh-Hant", "zh-TW"));
+        assert!(same_language("zh-HK", "zh-Hant"));
+        assert!(!same_language("zh-Hant", "zh"));
+        assert!(!same_language("zh-Hant", "zh-Hans"));
+        assert!(!same_language("zh-TW", "zh-CN"));
+    }
+
+    #[test]
+    fn same_language_compares_explicit_scripts() {
+        assert!(!same_language("sr-Latn", "sr-Cyrl"));
+        assert!(same_language("sr-Latn", "sr"));
+    }
+}
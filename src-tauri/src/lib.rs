@@ -1,58 +1,43 @@
+mod cache;
+mod detect;
+mod engines;
+mod error;
+mod glossary;
+mod history;
+mod http;
+mod languages;
+mod phrasebook;
+
 use arboard::Clipboard;
-use chrono::Utc;
+use cache::{CacheInfo, CacheKey, TranslationCache};
+use detect::{DetectedLanguage, LanguageDetector};
+use engines::azure::{AzureMultiTranslation, AzureTextType};
+use engines::deepl::DeeplGlossary;
+use engines::libretranslate::LibreLanguage;
+use engines::retry::RetryPolicy;
+use engines::{EngineCapabilities, Translation, TranslationEngine, TranslationRequest};
 use enigo::{Direction, Enigo, Key, Keyboard, Settings};
-use hex;
-use hmac::{Hmac, Mac};
+use error::{AppError, ErrorCode};
+use glossary::GlossaryEntry;
+use history::{HistoryEntry, HistoryFilter, HistorySearchHit, HistorySettings, HistoryStore};
+use http::{HttpClient, NetworkSettings};
+use languages::Language;
 use log::{debug, error, info, warn};
+use phrasebook::{NewPhrase, Phrase, PhrasebookStore};
 // use mouse_position::mouse_position::Mouse;
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::future::Future;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use std::{thread, time};
 use tauri::menu::{Menu, MenuItem};
 use tauri::tray::{MouseButton, TrayIconBuilder, TrayIconEvent};
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::{AppHandle, Emitter, Manager, State};
 use tauri_plugin_global_shortcut::GlobalShortcutExt;
 use window_vibrancy::{apply_vibrancy, NSVisualEffectMaterial};
 
-#[derive(Debug, Serialize)]
-struct GlmChatCompletionRequest {
-    model: String,
-    messages: Vec<GlmMessage>,
-    temperature: f32,
-    top_p: f32,
-    stream: bool,
-    do_sample: bool,
-    response_format: GlmResponseFormat,
-}
-
-#[derive(Debug, Serialize)]
-struct GlmResponseFormat {
-    #[serde(rename = "type")]
-    kind: String,
-}
-
-#[derive(Debug, Serialize)]
-struct GlmMessage {
-    role: String,
-    content: String,
-}
-
-#[derive(Debug, Deserialize)]
-struct GlmChatCompletionResponse {
-    choices: Vec<GlmChoice>,
-}
-
-#[derive(Debug, Deserialize)]
-struct GlmChoice {
-    message: GlmAssistantMessage,
-}
-
-#[derive(Debug, Deserialize)]
-struct GlmAssistantMessage {
-    content: String,
-}
-
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(default)]
 struct AppSettings {
@@ -60,7 +45,47 @@ struct AppSettings {
     tencent_secret_id: Option<String>,
     tencent_secret_key: Option<String>,
     tencent_region: Option<String>,
-    active_engine: String, // "zhipu" or "tencent"
+    tencent_term_repo_ids: Vec<String>,
+    openai_base_url: Option<String>,
+    openai_api_key: Option<String>,
+    openai_model: Option<String>,
+    ollama_host: Option<String>,
+    ollama_model: Option<String>,
+    deepl_api_key: Option<String>,
+    deepl_endpoint: Option<String>, // overrides the Free/Pro endpoint picked from the key
+    deepl_glossary: Option<DeeplGlossary>,
+    baidu_app_id: Option<String>,
+    baidu_secret_key: Option<String>,
+    youdao_app_key: Option<String>,
+    youdao_app_secret: Option<String>,
+    azure_subscription_key: Option<String>,
+    azure_region: Option<String>,
+    azure_endpoint: Option<String>,
+    azure_text_type: AzureTextType,
+    anthropic_api_key: Option<String>,
+    anthropic_base_url: Option<String>,
+    anthropic_model: Option<String>,
+    gemini_api_key: Option<String>,
+    gemini_model: Option<String>,
+    gemini_endpoint: Option<String>,
+    libretranslate_url: Option<String>,
+    libretranslate_api_key: Option<String>,
+    // Mirror the translation cache to the config dir so it survives restarts
+    disk_cache_enabled: bool,
+    history: HistorySettings,
+    glossary: Vec<GlossaryEntry>,
+    language_detector: LanguageDetector,
+    // Translate text already in the target language into `secondary_lang`
+    auto_direction: bool,
+    secondary_lang: Option<String>,
+    active_engine: String, // engine id, see `engines::find_engine`
+    // Engines tried in order when the active one fails
+    fallback_engines: Vec<String>,
+    retry_policy: RetryPolicy,
+    // Per-engine overrides of `retry_policy`, keyed by engine id
+    engine_retry_policies: HashMap<String, RetryPolicy>,
+    // Proxy, timeouts and extra root certificates for every engine request
+    network: NetworkSettings,
 }
 
 impl Default for AppSettings {
@@ -70,59 +95,110 @@ impl Default for AppSettings {
             tencent_secret_id: None,
             tencent_secret_key: None,
             tencent_region: Some("ap-guangzhou".to_string()),
+            tencent_term_repo_ids: Vec::new(),
+            openai_base_url: None,
+            openai_api_key: None,
+            openai_model: None,
+            ollama_host: Some(engines::ollama::DEFAULT_OLLAMA_HOST.to_string()),
+            ollama_model: None,
+            deepl_api_key: None,
+            deepl_endpoint: None,
+            deepl_glossary: None,
+            baidu_app_id: None,
+            baidu_secret_key: None,
+            youdao_app_key: None,
+            youdao_app_secret: None,
+            azure_subscription_key: None,
+            azure_region: None,
+            azure_endpoint: None,
+            azure_text_type: AzureTextType::Plain,
+            anthropic_api_key: None,
+            anthropic_base_url: None,
+            anthropic_model: None,
+            gemini_api_key: None,
+            gemini_model: None,
+            gemini_endpoint: None,
+            libretranslate_url: None,
+            libretranslate_api_key: None,
+            disk_cache_enabled: false,
+            history: HistorySettings::default(),
+            glossary: Vec::new(),
+            language_detector: LanguageDetector::Offline,
+            auto_direction: false,
+            secondary_lang: Some("zh".to_string()),
             active_engine: "zhipu".to_string(),
+            fallback_engines: Vec::new(),
+            retry_policy: RetryPolicy::default(),
+            engine_retry_policies: HashMap::new(),
+            network: NetworkSettings::default(),
         }
     }
 }
 
-fn app_settings_path(app: &AppHandle) -> Result<PathBuf, String> {
+fn app_settings_path(app: &AppHandle) -> Result<PathBuf, AppError> {
     let dir = app
         .path()
         .app_config_dir()
-        .map_err(|e| format!("Failed to resolve app config dir: {}", e))?;
+        .map_err(|e| AppError::storage(format!("Failed to resolve app config dir: {}", e)))?;
     Ok(dir.join("settings.json"))
 }
 
-async fn read_app_settings(app: &AppHandle) -> Result<AppSettings, String> {
+async fn read_app_settings(app: &AppHandle) -> Result<AppSettings, AppError> {
     let path = app_settings_path(app)?;
     let data = match tokio::fs::read_to_string(&path).await {
         Ok(data) => data,
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(AppSettings::default()),
-        Err(e) => return Err(format!("Failed to read settings: {}", e)),
+        Err(e) => return Err(AppError::storage(format!("Failed to read settings: {}", e))),
     };
 
-    serde_json::from_str(&data).map_err(|e| format!("Failed to parse settings: {}", e))
+    serde_json::from_str(&data)
+        .map_err(|e| AppError::storage(format!("Failed to parse settings: {}", e)))
 }
 
-async fn write_app_settings(app: &AppHandle, settings: &AppSettings) -> Result<(), String> {
+async fn write_app_settings(app: &AppHandle, settings: &AppSettings) -> Result<(), AppError> {
     let path = app_settings_path(app)?;
     if let Some(dir) = path.parent() {
         tokio::fs::create_dir_all(dir)
             .await
-            .map_err(|e| format!("Failed to create settings dir: {}", e))?;
+            .map_err(|e| AppError::storage(format!("Failed to create settings dir: {}", e)))?;
     }
     let data = serde_json::to_string_pretty(settings)
-        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+        .map_err(|e| AppError::storage(format!("Failed to serialize settings: {}", e)))?;
     tokio::fs::write(&path, data)
         .await
-        .map_err(|e| format!("Failed to write settings: {}", e))?;
+        .map_err(|e| AppError::storage(format!("Failed to write settings: {}", e)))?;
     Ok(())
 }
 
+/// The shared HTTP client for the current network settings
+fn http_client(app: &AppHandle, settings: &AppSettings) -> Result<reqwest::Client, AppError> {
+    app.state::<HttpClient>().get(&settings.network)
+}
+
+/// Trim user input and treat blank values as unset
+fn non_empty(value: String) -> Option<String> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        None
+    } else {
+        Some(trimmed.to_string())
+    }
+}
+
+/// `None` for a blank or "auto" source language, which engines detect themselves
+fn source_language(lang: String) -> Option<String> {
+    non_empty(lang).filter(|lang| !lang.eq_ignore_ascii_case("auto"))
+}
+
 #[tauri::command]
-async fn get_app_settings(app: AppHandle) -> Result<AppSettings, String> {
+async fn get_app_settings(app: AppHandle) -> Result<AppSettings, AppError> {
     read_app_settings(&app).await
 }
 
 #[tauri::command]
-async fn set_zhipu_api_key(app: AppHandle, api_key: String) -> Result<(), String> {
+async fn set_zhipu_api_key(app: AppHandle, api_key: String) -> Result<(), AppError> {
     let mut settings = read_app_settings(&app).await.unwrap_or_default();
-    let trimmed = api_key.trim().to_string();
-    settings.zhipu_api_key = if trimmed.is_empty() {
-        None
-    } else {
-        Some(trimmed)
-    };
+    settings.zhipu_api_key = non_empty(api_key);
     write_app_settings(&app, &settings).await
 }
 
@@ -132,314 +208,1141 @@ async fn set_tencent_config(
     secret_id: String,
     secret_key: String,
     region: String,
-) -> Result<(), String> {
+    term_repo_ids: Option<Vec<String>>,
+) -> Result<(), AppError> {
     let mut settings = read_app_settings(&app).await.unwrap_or_default();
 
-    let secret_id = secret_id.trim().to_string();
-    settings.tencent_secret_id = if secret_id.is_empty() {
-        None
-    } else {
-        Some(secret_id)
-    };
+    settings.tencent_secret_id = non_empty(secret_id);
+    settings.tencent_secret_key = non_empty(secret_key);
+    settings.tencent_region = non_empty(region).or_else(|| Some("ap-guangzhou".to_string()));
+    if let Some(ids) = term_repo_ids {
+        settings.tencent_term_repo_ids = ids.into_iter().filter_map(non_empty).collect();
+    }
 
-    let secret_key = secret_key.trim().to_string();
-    settings.tencent_secret_key = if secret_key.is_empty() {
-        None
-    } else {
-        Some(secret_key)
-    };
+    write_app_settings(&app, &settings).await
+}
 
-    let region = region.trim().to_string();
-    settings.tencent_region = if region.is_empty() {
-        Some("ap-guangzhou".to_string())
-    } else {
-        Some(region)
+#[tauri::command]
+async fn set_openai_config(
+    app: AppHandle,
+    base_url: String,
+    api_key: String,
+    model: String,
+) -> Result<(), AppError> {
+    let mut settings = read_app_settings(&app).await.unwrap_or_default();
+
+    settings.openai_base_url = non_empty(base_url);
+    settings.openai_api_key = non_empty(api_key);
+    settings.openai_model = non_empty(model);
+
+    write_app_settings(&app, &settings).await
+}
+
+#[tauri::command]
+async fn set_ollama_config(app: AppHandle, host: String, model: String) -> Result<(), AppError> {
+    let mut settings = read_app_settings(&app).await.unwrap_or_default();
+
+    settings.ollama_host =
+        non_empty(host).or_else(|| Some(engines::ollama::DEFAULT_OLLAMA_HOST.to_string()));
+    settings.ollama_model = non_empty(model);
+
+    write_app_settings(&app, &settings).await
+}
+
+// List models installed on the Ollama server, defaulting to the saved host
+#[tauri::command]
+async fn list_ollama_models(app: AppHandle, host: Option<String>) -> Result<Vec<String>, AppError> {
+    let settings = read_app_settings(&app).await.unwrap_or_default();
+    let client = http_client(&app, &settings)?;
+    let host = host
+        .and_then(non_empty)
+        .or(settings.ollama_host)
+        .unwrap_or_else(|| engines::ollama::DEFAULT_OLLAMA_HOST.to_string());
+    engines::ollama::list_models(&client, &host)
+        .await
+        .map_err(|e| e.with_engine("ollama"))
+}
+
+#[tauri::command]
+async fn set_deepl_config(
+    app: AppHandle,
+    api_key: String,
+    endpoint: String,
+    glossary_id: String,
+) -> Result<(), AppError> {
+    let mut settings = read_app_settings(&app).await.unwrap_or_default();
+
+    settings.deepl_api_key = non_empty(api_key);
+    settings.deepl_endpoint = non_empty(endpoint);
+
+    // Resolve the glossary's language pair once so translations don't need an extra request
+    settings.deepl_glossary = match (non_empty(glossary_id), settings.deepl_api_key.as_deref()) {
+        (Some(glossary_id), Some(api_key)) => Some(
+            engines::deepl::get_glossary(
+                &http_client(&app, &settings)?,
+                api_key,
+                settings.deepl_endpoint.as_deref(),
+                &glossary_id,
+            )
+            .await
+            .map_err(|e| e.with_engine("deepl"))?,
+        ),
+        _ => None,
     };
 
     write_app_settings(&app, &settings).await
 }
 
 #[tauri::command]
-async fn set_active_engine(app: AppHandle, engine: String) -> Result<(), String> {
+async fn list_deepl_glossaries(app: AppHandle) -> Result<Vec<DeeplGlossary>, AppError> {
+    let settings = read_app_settings(&app).await.unwrap_or_default();
+    let api_key = settings
+        .deepl_api_key
+        .as_deref()
+        .ok_or_else(|| AppError::invalid_config("未配置 DeepL API Key").with_engine("deepl"))?;
+    let client = http_client(&app, &settings)?;
+    engines::deepl::list_glossaries(&client, api_key, settings.deepl_endpoint.as_deref())
+        .await
+        .map_err(|e| e.with_engine("deepl"))
+}
+
+#[tauri::command]
+async fn set_baidu_config(
+    app: AppHandle,
+    app_id: String,
+    secret_key: String,
+) -> Result<(), AppError> {
+    let mut settings = read_app_settings(&app).await.unwrap_or_default();
+
+    settings.baidu_app_id = non_empty(app_id);
+    settings.baidu_secret_key = non_empty(secret_key);
+
+    write_app_settings(&app, &settings).await
+}
+
+#[tauri::command]
+async fn set_youdao_config(
+    app: AppHandle,
+    app_key: String,
+    app_secret: String,
+) -> Result<(), AppError> {
+    let mut settings = read_app_settings(&app).await.unwrap_or_default();
+
+    settings.youdao_app_key = non_empty(app_key);
+    settings.youdao_app_secret = non_empty(app_secret);
+
+    write_app_settings(&app, &settings).await
+}
+
+#[tauri::command]
+async fn set_azure_config(
+    app: AppHandle,
+    subscription_key: String,
+    region: String,
+    endpoint: String,
+    text_type: AzureTextType,
+) -> Result<(), AppError> {
     let mut settings = read_app_settings(&app).await.unwrap_or_default();
-    match engine.as_str() {
-        "zhipu" | "tencent" => {
-            settings.active_engine = engine;
-            write_app_settings(&app, &settings).await
+
+    settings.azure_subscription_key = non_empty(subscription_key);
+    settings.azure_region = non_empty(region);
+    settings.azure_endpoint = non_empty(endpoint);
+    settings.azure_text_type = text_type;
+
+    write_app_settings(&app, &settings).await
+}
+
+// Translate into several languages at once through Azure
+#[tauri::command]
+async fn translate_azure_multi(
+    app: AppHandle,
+    text: String,
+    source_lang: Option<String>,
+    target_langs: Vec<String>,
+    text_type: Option<AzureTextType>,
+) -> Result<AzureMultiTranslation, AppError> {
+    let settings = read_app_settings(&app).await.unwrap_or_default();
+    let text_type = text_type.unwrap_or(settings.azure_text_type);
+    let source_lang = source_lang.and_then(source_language);
+    let client = http_client(&app, &settings)?;
+    engines::azure::translate_multi(
+        &client,
+        &settings,
+        &text,
+        source_lang.as_deref(),
+        &target_langs,
+        text_type,
+    )
+    .await
+    .map_err(|e| e.with_engine("azure"))
+}
+
+#[tauri::command]
+async fn set_anthropic_config(
+    app: AppHandle,
+    api_key: String,
+    base_url: String,
+    model: String,
+) -> Result<(), AppError> {
+    let mut settings = read_app_settings(&app).await.unwrap_or_default();
+
+    settings.anthropic_api_key = non_empty(api_key);
+    settings.anthropic_base_url = non_empty(base_url);
+    settings.anthropic_model = non_empty(model);
+
+    write_app_settings(&app, &settings).await
+}
+
+#[tauri::command]
+async fn set_gemini_config(
+    app: AppHandle,
+    api_key: String,
+    model: String,
+    endpoint: String,
+) -> Result<(), AppError> {
+    let mut settings = read_app_settings(&app).await.unwrap_or_default();
+
+    settings.gemini_api_key = non_empty(api_key);
+    settings.gemini_model = non_empty(model);
+    settings.gemini_endpoint = non_empty(endpoint);
+
+    write_app_settings(&app, &settings).await
+}
+
+#[tauri::command]
+async fn set_libretranslate_config(
+    app: AppHandle,
+    url: String,
+    api_key: String,
+) -> Result<(), AppError> {
+    let mut settings = read_app_settings(&app).await.unwrap_or_default();
+
+    settings.libretranslate_url = non_empty(url);
+    settings.libretranslate_api_key = non_empty(api_key);
+
+    write_app_settings(&app, &settings).await
+}
+
+// Language pairs supported by the configured LibreTranslate instance
+#[tauri::command]
+async fn list_libretranslate_languages(app: AppHandle) -> Result<Vec<LibreLanguage>, AppError> {
+    let settings = read_app_settings(&app).await.unwrap_or_default();
+    let client = http_client(&app, &settings)?;
+    engines::libretranslate::list_languages(&client, &settings)
+        .await
+        .map_err(|e| e.with_engine("libretranslate"))
+}
+
+// Replace the whole glossary
+#[tauri::command]
+async fn set_glossary(app: AppHandle, entries: Vec<GlossaryEntry>) -> Result<(), AppError> {
+    let mut settings = read_app_settings(&app).await.unwrap_or_default();
+    settings.glossary = glossary::normalize(entries);
+    write_app_settings(&app, &settings).await
+}
+
+#[tauri::command]
+async fn set_language_detection_config(
+    app: AppHandle,
+    detector: LanguageDetector,
+    auto_direction: bool,
+    secondary_lang: String,
+) -> Result<(), AppError> {
+    let mut settings = read_app_settings(&app).await.unwrap_or_default();
+
+    settings.language_detector = detector;
+    settings.auto_direction = auto_direction;
+    settings.secondary_lang = non_empty(secondary_lang);
+
+    write_app_settings(&app, &settings).await
+}
+
+#[tauri::command]
+async fn detect_language(app: AppHandle, text: String) -> Result<DetectedLanguage, AppError> {
+    let settings = read_app_settings(&app).await.unwrap_or_default();
+    let client = http_client(&app, &settings)?;
+    detect::detect(&client, &settings, &text).await
+}
+
+#[tauri::command]
+async fn set_active_engine(app: AppHandle, engine: String) -> Result<(), AppError> {
+    let mut settings = read_app_settings(&app).await.unwrap_or_default();
+    if engines::find_engine(&engine).is_none() {
+        return Err(AppError::invalid_config("Invalid engine name"));
+    }
+    settings.active_engine = engine;
+    write_app_settings(&app, &settings).await
+}
+
+#[tauri::command]
+async fn set_fallback_engines(app: AppHandle, engines: Vec<String>) -> Result<(), AppError> {
+    let mut settings = read_app_settings(&app).await.unwrap_or_default();
+    if engines.iter().any(|id| engines::find_engine(id).is_none()) {
+        return Err(AppError::invalid_config("Invalid engine name"));
+    }
+    settings.fallback_engines = engines;
+    write_app_settings(&app, &settings).await
+}
+
+// Set the default retry policy, or with `engine` that engine's override;
+// a `None` policy removes the override
+#[tauri::command]
+async fn set_retry_policy(
+    app: AppHandle,
+    engine: Option<String>,
+    policy: Option<RetryPolicy>,
+) -> Result<(), AppError> {
+    let mut settings = read_app_settings(&app).await.unwrap_or_default();
+    match engine {
+        Some(engine) => {
+            if engines::find_engine(&engine).is_none() {
+                return Err(AppError::invalid_config("Invalid engine name"));
+            }
+            match policy {
+                Some(policy) => settings.engine_retry_policies.insert(engine, policy),
+                None => settings.engine_retry_policies.remove(&engine),
+            };
         }
-        _ => Err("Invalid engine name".to_string()),
+        None => settings.retry_policy = policy.unwrap_or_default(),
     }
+    write_app_settings(&app, &settings).await
+}
+
+// Proxy, timeouts and root certificates, checked by building a client first;
+// zero timeouts are rejected
+#[tauri::command]
+async fn set_network_settings(app: AppHandle, network: NetworkSettings) -> Result<(), AppError> {
+    http::build(&network)?;
+    let mut settings = read_app_settings(&app).await.unwrap_or_default();
+    settings.network = network;
+    write_app_settings(&app, &settings).await
 }
 
-fn normalize_target_language(target_lang: &str) -> String {
-    let normalized = target_lang.trim().to_ascii_uppercase();
-    match normalized.as_str() {
-        "ZH" | "ZH-CN" | "ZH_CN" | "ZH-HANS" | "CHINESE" => "中文".to_string(),
-        "EN" | "EN-US" | "EN_GB" | "EN-GB" | "ENGLISH" => "英文".to_string(),
-        "JA" | "JP" | "JAPANESE" => "日文".to_string(),
-        _ => target_lang.trim().to_string(),
+#[tauri::command]
+fn list_engines() -> Vec<EngineCapabilities> {
+    engines::all_engines()
+        .map(|engine| engine.capabilities())
+        .collect()
+}
+
+/// Languages the frontend offers for `engine`, or for the active engine
+#[tauri::command]
+async fn list_languages(app: AppHandle, engine: Option<String>) -> Vec<Language> {
+    let engine = match engine {
+        Some(id) => engines::find_engine(&id),
+        None => {
+            let settings = read_app_settings(&app).await.unwrap_or_default();
+            engines::find_engine(&settings.active_engine)
+        }
+    };
+    engine
+        .unwrap_or_else(engines::default_engine)
+        .supported_languages()
+}
+
+/// The active engine followed by the fallback engines, without duplicates
+fn engine_chain(settings: &AppSettings) -> Vec<&'static dyn TranslationEngine> {
+    let active = engines::find_engine(&settings.active_engine).unwrap_or_else(|| {
+        warn!(
+            "[get_translation] Unknown engine '{}', falling back to default",
+            settings.active_engine
+        );
+        engines::default_engine()
+    });
+
+    let mut chain = vec![active];
+    for id in &settings.fallback_engines {
+        match engines::find_engine(id) {
+            Some(engine) if chain.iter().all(|e| e.id() != engine.id()) => chain.push(engine),
+            Some(_) => {}
+            None => warn!("[fallback] Unknown engine '{}' ignored", id),
+        }
     }
+    chain
 }
 
-fn strip_code_fences(text: &str) -> String {
-    let trimmed = text.trim();
-    if trimmed.starts_with("```") && trimmed.ends_with("```") {
-        let without_start = trimmed.trim_start_matches("```");
-        let without_lang = without_start
-            .trim_start_matches(|c: char| c.is_ascii_alphanumeric() || c == '-')
-            .trim_start_matches(|c: char| c == '\n' || c == '\r' || c == ' ');
-        let without_end = without_lang.trim_end_matches("```").trim();
-        return without_end.to_string();
+/// Translations started with a request id, so the frontend can cancel
+/// requests that were superseded by newer input. An id is registered for the
+/// whole command and holds the abort handle while an engine call runs.
+///
+/// Ids come from a frontend counter that restarts when the window reloads,
+/// so each registration also gets a generation. A request only touches the
+/// entry while it still holds its own generation.
+#[derive(Default)]
+struct InFlightTranslations(Mutex<InFlightState>);
+
+#[derive(Default)]
+struct InFlightState {
+    next_generation: u64,
+    /// Generation and running engine call of each registered id
+    requests: HashMap<u64, (u64, Option<tokio::task::AbortHandle>)>,
+}
+
+impl InFlightTranslations {
+    /// Register `request_id` until the returned guard is dropped. A cancel
+    /// that arrives before the engine call, e.g. during language detection,
+    /// then keeps the call from being made. Registering an id that is still
+    /// in use takes it over from the older request.
+    fn register(&self, request_id: u64) -> InFlightRequest<'_> {
+        let mut state = self.0.lock().unwrap();
+        state.next_generation += 1;
+        let generation = state.next_generation;
+        state.requests.insert(request_id, (generation, None));
+        InFlightRequest {
+            tasks: self,
+            request_id,
+            generation,
+        }
+    }
+
+    /// Unregister `request_id`, aborting its engine call if one is running.
+    /// Returns false if the request isn't registered.
+    fn cancel(&self, request_id: u64) -> bool {
+        match self.0.lock().unwrap().requests.remove(&request_id) {
+            Some((_, handle)) => {
+                if let Some(handle) = handle {
+                    handle.abort();
+                }
+                true
+            }
+            None => false,
+        }
     }
-    trimmed.to_string()
 }
 
-// --- Tencent Cloud Implementation ---
+struct InFlightRequest<'a> {
+    tasks: &'a InFlightTranslations,
+    request_id: u64,
+    generation: u64,
+}
+
+impl InFlightRequest<'_> {
+    /// The abort handle slot of this request, unless it was cancelled or its
+    /// id was taken over by a newer request
+    fn with_entry<R>(
+        &self,
+        f: impl FnOnce(&mut Option<tokio::task::AbortHandle>) -> R,
+    ) -> Option<R> {
+        let mut state = self.tasks.0.lock().unwrap();
+        match state.requests.get_mut(&self.request_id) {
+            Some((generation, handle)) if *generation == self.generation => Some(f(handle)),
+            _ => None,
+        }
+    }
+
+    /// Run `translation` as its own task so that `cancel_translation` can
+    /// abort it, dropping the in-flight HTTP request. Nothing runs if the
+    /// request was cancelled already.
+    async fn run<F, T>(&self, translation: F) -> Result<T, AppError>
+    where
+        F: Future<Output = Result<T, AppError>> + Send + 'static,
+        T: Send + 'static,
+    {
+        let task = self.with_entry(|handle| {
+            let task = tokio::spawn(translation);
+            *handle = Some(task.abort_handle());
+            task
+        });
+        let Some(task) = task else {
+            return Err(translation_cancelled(self.request_id));
+        };
+        let result = task.await;
+        self.with_entry(|handle| *handle = None);
+
+        match result {
+            Ok(result) => result,
+            Err(e) if e.is_cancelled() => Err(translation_cancelled(self.request_id)),
+            Err(e) => Err(AppError::new(
+                ErrorCode::Internal,
+                format!("Translation task failed: {}", e),
+            )),
+        }
+    }
+}
 
-#[derive(Serialize)]
-#[allow(non_snake_case)]
-struct TencentRequest<'a> {
-    SourceText: &'a str,
-    Source: &'a str,
-    Target: &'a str,
-    ProjectId: i64,
+impl Drop for InFlightRequest<'_> {
+    fn drop(&mut self) {
+        let mut state = self.tasks.0.lock().unwrap();
+        if let Some((generation, _)) = state.requests.get(&self.request_id) {
+            if *generation == self.generation {
+                state.requests.remove(&self.request_id);
+            }
+        }
+    }
 }
 
-#[derive(Deserialize)]
-struct TencentResponse {
-    #[serde(rename = "Response")]
-    response: TencentResponseData,
+fn translation_cancelled(request_id: u64) -> AppError {
+    info!("[translation] #{} cancelled", request_id);
+    AppError::new(ErrorCode::Cancelled, "翻译已取消")
 }
 
-#[derive(Deserialize)]
-struct TencentResponseData {
-    #[serde(rename = "TargetText")]
-    target_text: String,
-    #[serde(rename = "Error")]
-    error: Option<TencentError>,
+/// Add a finished translation to the history; failures are only logged
+fn record_history(
+    app: &AppHandle,
+    settings: HistorySettings,
+    engine: &str,
+    request: &TranslationRequest,
+    translation: &Translation,
+) {
+    if let Some(store) = app.try_state::<HistoryStore>() {
+        if let Err(e) = store.record(settings, engine, request, translation) {
+            error!("[history] Failed to record translation: {}", e);
+        }
+    }
 }
 
-#[derive(Deserialize)]
-struct TencentError {
-    #[serde(rename = "Code")]
-    code: String,
-    #[serde(rename = "Message")]
-    message: String,
+/// Apply the history retention period to entries already stored, e.g. on
+/// startup or after it was shortened; failures are only logged
+async fn prune_history(app: &AppHandle) {
+    let Some(store) = app.try_state::<HistoryStore>() else {
+        return;
+    };
+    let settings = read_app_settings(app).await.unwrap_or_default();
+    match store.prune(settings.history.retention_days) {
+        Ok(0) => {}
+        Ok(count) => info!("[history] Pruned {} expired entries", count),
+        Err(e) => error!("[history] Failed to prune history: {}", e),
+    }
 }
 
-async fn translate_tencent(
-    settings: AppSettings,
+/// Build the engine request. Returns the target picked by auto direction
+/// as well, if it replaced the requested one.
+async fn translation_request(
+    client: &reqwest::Client,
+    settings: &AppSettings,
     text: String,
+    source_lang: Option<String>,
     target_lang: String,
-) -> Result<String, String> {
-    let secret_id = settings.tencent_secret_id.ok_or("未配置腾讯云 SecretId")?;
-    let secret_key = settings
-        .tencent_secret_key
-        .ok_or("未配置腾讯云 SecretKey")?;
-    let region = settings
-        .tencent_region
-        .unwrap_or_else(|| "ap-guangzhou".to_string());
-
-    let host = "tmt.tencentcloudapi.com";
-    let service = "tmt";
-    let action = "TextTranslate";
-    let version = "2018-03-21";
-    let timestamp = Utc::now().timestamp();
-    let date = Utc::now().format("%Y-%m-%d").to_string();
-
-    let target = match target_lang.to_ascii_lowercase().as_str() {
-        "zh" | "zh-cn" => "zh",
-        "en" | "en-us" => "en",
-        "ja" | "jp" => "ja",
-        "ko" | "kr" => "ko",
-        _ => &target_lang,
-    };
+    tone: String,
+) -> (TranslationRequest, Option<String>) {
+    let auto_target = detect::resolve_target_lang(client, settings, &text, &target_lang).await;
+    if let Some(auto_target) = &auto_target {
+        info!(
+            "[auto_direction] Text is already in {}, translating to {}",
+            target_lang, auto_target
+        );
+    }
 
-    let payload = TencentRequest {
-        SourceText: &text,
-        Source: "auto",
-        Target: target,
-        ProjectId: 0,
+    // A flipped request's text is in the requested target language, whatever
+    // source was selected
+    let (source_lang, target_lang) = match &auto_target {
+        Some(auto_target) => (Some(target_lang), auto_target.clone()),
+        None => (source_lang.and_then(source_language), target_lang),
     };
-    let payload_str = serde_json::to_string(&payload).map_err(|e| e.to_string())?;
-
-    // Signature V3
-    let canonical_headers = format!("content-type:application/json\nhost:{}\n", host);
-    let signed_headers = "content-type;host";
-    let hashed_request_payload = hex::encode(Sha256::digest(payload_str.as_bytes()));
-    let canonical_request = format!(
-        "POST\n/\n\n{}\n{}\n{}",
-        canonical_headers, signed_headers, hashed_request_payload
-    );
-
-    let credential_scope = format!("{}/{}/tc3_request", date, service);
-    let hashed_canonical_request = hex::encode(Sha256::digest(canonical_request.as_bytes()));
-    let string_to_sign = format!(
-        "TC3-HMAC-SHA256\n{}\n{}\n{}",
-        timestamp, credential_scope, hashed_canonical_request
-    );
+    let request = TranslationRequest {
+        text,
+        source_lang,
+        target_lang,
+        tone,
+    };
+    (request, auto_target)
+}
 
-    let k_secret = format!("TC3{}", secret_key);
-    let k_date = hmac_sha256(k_secret.as_bytes(), date.as_bytes());
-    let k_service = hmac_sha256(&k_date, service.as_bytes());
-    let k_signing = hmac_sha256(&k_service, "tc3_request".as_bytes());
-    let signature = hex::encode(hmac_sha256(&k_signing, string_to_sign.as_bytes()));
+/// Glossary check and history recording shared by the translation commands
+fn finish_translation(
+    app: &AppHandle,
+    settings: &AppSettings,
+    engine: &str,
+    request: &TranslationRequest,
+    auto_target: Option<String>,
+    result: Result<Translation, AppError>,
+) -> Result<Translation, AppError> {
+    let mut translation = result?;
+    translation.engine = Some(engine.to_string());
+    translation.auto_target_lang = auto_target;
+    translation.glossary_violations =
+        glossary::verify(&settings.glossary, request, &translation.text);
+    if !translation.glossary_violations.is_empty() {
+        warn!(
+            "[glossary] '{}' ignored {} glossary term(s)",
+            engine,
+            translation.glossary_violations.len()
+        );
+    }
 
-    let authorization = format!(
-        "TC3-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
-        secret_id, credential_scope, signed_headers, signature
-    );
+    record_history(app, settings.history, engine, request, &translation);
+    Ok(translation)
+}
 
-    let client = reqwest::Client::new();
-    let res = client
-        .post(format!("https://{}", host))
-        .header("Content-Type", "application/json")
-        .header("Authorization", authorization)
-        .header("Host", host)
-        .header("X-TC-Action", action)
-        .header("X-TC-Version", version)
-        .header("X-TC-Timestamp", timestamp.to_string())
-        .header("X-TC-Region", region)
-        .body(payload_str)
-        .send()
-        .await
-        .map_err(|e| format!("Request failed: {}", e))?;
+/// Translate with each engine of the chain in turn until one succeeds, going
+/// through the cache for each; `on_cache_hit` sees cached results, which
+/// skip `translate`. Returns the engine that produced the result, or the last
+/// one tried.
+///
+/// Failures another engine may not have, e.g. network, auth, quota or a
+/// missing config, move on to the next engine; cancellation and rejected
+/// requests end the chain.
+#[allow(clippy::too_many_arguments)]
+async fn translate_with_fallback<F, Fut, H>(
+    app: &AppHandle,
+    in_flight: Option<&InFlightRequest<'_>>,
+    cache: &TranslationCache,
+    settings: &AppSettings,
+    request: &TranslationRequest,
+    translate: F,
+    on_cache_hit: H,
+) -> (
+    &'static dyn TranslationEngine,
+    Result<Translation, AppError>,
+)
+where
+    F: Fn(&'static dyn TranslationEngine) -> Fut,
+    Fut: Future<Output = Result<Translation, AppError>> + Send + 'static,
+    H: Fn(&Translation),
+{
+    let chain = engine_chain(settings);
+    let mut errors = Vec::new();
+
+    for &engine in &chain {
+        if !errors.is_empty() {
+            info!("[fallback] Trying '{}'", engine.id());
+        }
 
-    let resp_data: TencentResponse = res
-        .json()
-        .await
-        .map_err(|e| format!("Parse error: {}", e))?;
+        let result = match engine.validate_config(settings) {
+            Ok(()) => {
+                let key = CacheKey::new(engine, settings, request);
+                match cache.get(app, settings.disk_cache_enabled, &key).await {
+                    Some(translation) => {
+                        info!("[translation] '{}' cache hit", engine.id());
+                        on_cache_hit(&translation);
+                        Ok(translation)
+                    }
+                    None => {
+                        let translation = translate(engine);
+                        let result = match in_flight {
+                            Some(in_flight) => in_flight.run(translation).await,
+                            None => translation.await,
+                        };
+                        if let Ok(translation) = &result {
+                            cache.insert(
+                                app,
+                                settings.disk_cache_enabled,
+                                key,
+                                translation.clone(),
+                            );
+                        }
+                        result
+                    }
+                }
+            }
+            Err(e) => Err(e),
+        };
 
-    if let Some(err) = resp_data.response.error {
-        return Err(format!("Tencent API Error ({}): {}", err.code, err.message));
+        match result.map_err(|e| e.with_engine(engine.id())) {
+            Ok(translation) => return (engine, Ok(translation)),
+            Err(e) => {
+                error!("[translation] '{}' failed: {}", engine.id(), e);
+                let fall_back = e.should_fall_back();
+                errors.push(e);
+                if !fall_back {
+                    return (engine, Err(combine_errors(errors)));
+                }
+            }
+        }
     }
 
-    Ok(resp_data.response.target_text)
+    (chain[chain.len() - 1], Err(combine_errors(errors)))
 }
 
-fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
-    type HmacSha256 = Hmac<Sha256>;
-    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC can take any key length");
-    mac.update(data);
-    mac.finalize().into_bytes().to_vec()
+/// The first engine's error, so its code drives the UI, with the details of
+/// every engine tried
+fn combine_errors(errors: Vec<AppError>) -> AppError {
+    let mut errors = errors.into_iter();
+    let mut combined = errors.next().expect("the chain holds at least one engine");
+    for e in errors {
+        combined.details.push('\n');
+        combined.details.push_str(&e.to_string());
+    }
+    combined
 }
 
-// Get translation from Active Engine
+// Get translation from Active Engine, or the first fallback engine that succeeds
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 async fn get_translation(
     app: AppHandle,
+    tasks: State<'_, InFlightTranslations>,
+    cache: State<'_, TranslationCache>,
     text: String,
+    source_lang: Option<String>,
     target_lang: String,
     tone: String,
-) -> Result<String, String> {
-    let settings = read_app_settings(&app).await.unwrap_or_default();
+    request_id: Option<u64>,
+) -> Result<Translation, AppError> {
+    // Registered before loading anything so an early cancel isn't lost
+    let in_flight = request_id.map(|request_id| tasks.register(request_id));
+    let settings = Arc::new(read_app_settings(&app).await.unwrap_or_default());
+    let client = http_client(&app, &settings)?;
+
+    info!(
+        "[get_translation] Active engine '{}'. Text length: {}, source: {:?}, target: {}, tone: {}",
+        settings.active_engine,
+        text.len(),
+        source_lang,
+        target_lang,
+        tone
+    );
 
-    if settings.active_engine == "tencent" {
-        return translate_tencent(settings, text, target_lang).await;
+    let (request, auto_target) =
+        translation_request(&client, &settings, text, source_lang, target_lang, tone).await;
+    let (engine, result) = translate_with_fallback(
+        &app,
+        in_flight.as_ref(),
+        &cache,
+        &settings,
+        &request,
+        |engine| {
+            let client = client.clone();
+            let settings = settings.clone();
+            let request = request.clone();
+            async move { engine.translate(&client, &settings, &request).await }
+        },
+        |_| {},
+    )
+    .await;
+
+    finish_translation(&app, &settings, engine.id(), &request, auto_target, result)
+}
+
+// Abort an in-flight translation; returns false if it already finished
+#[tauri::command]
+fn cancel_translation(tasks: State<'_, InFlightTranslations>, request_id: u64) -> bool {
+    tasks.cancel(request_id)
+}
+
+#[derive(Clone, Serialize)]
+struct TranslationDelta {
+    request_id: u64,
+    delta: String,
+}
+
+/// Tells the translator to drop the deltas of a failed attempt before the
+/// next engine in the fallback chain starts streaming
+#[derive(Clone, Serialize)]
+struct TranslationReset {
+    request_id: u64,
+}
+
+#[derive(Clone, Serialize)]
+struct TranslationDone {
+    request_id: u64,
+    translation: Option<Translation>,
+    error: Option<AppError>,
+}
+
+fn emit_delta(app: &AppHandle, request_id: u64, delta: &str) {
+    let payload = TranslationDelta {
+        request_id,
+        delta: delta.to_string(),
+    };
+    if let Err(e) = app.emit_to("translator", "translation-delta", payload) {
+        error!("[get_translation_stream] Failed to emit delta: {}", e);
     }
+}
+
+// Streaming variant of get_translation: partial output is emitted to the
+// translator window as `translation-delta`, followed by `translation-done`.
+// `translation-reset` precedes each fallback attempt after the first. A
+// cached result arrives as a single delta.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn get_translation_stream(
+    app: AppHandle,
+    tasks: State<'_, InFlightTranslations>,
+    cache: State<'_, TranslationCache>,
+    request_id: u64,
+    text: String,
+    source_lang: Option<String>,
+    target_lang: String,
+    tone: String,
+) -> Result<Translation, AppError> {
+    let in_flight = tasks.register(request_id);
+    let settings = Arc::new(read_app_settings(&app).await.unwrap_or_default());
+    let client = http_client(&app, &settings)?;
 
-    // Default to Zhipu (GLM)
     info!(
-        "[get_translation] Using Zhipu AI. Text length: {}, target: {}, tone: {}",
+        "[get_translation_stream] #{} Active engine '{}'. Text length: {}, source: {:?}, target: {}, tone: {}",
+        request_id,
+        settings.active_engine,
         text.len(),
+        source_lang,
         target_lang,
         tone
     );
 
-    let api_key = settings
-        .zhipu_api_key
-        .or_else(|| std::env::var("ZHIPU_API_KEY").ok())
-        .ok_or_else(|| {
-            error!("[get_translation] No API key configured");
-            "未配置智谱 AI API Key：请在设置页配置".to_string()
-        })?;
-
-    let target = normalize_target_language(&target_lang);
-
-    // Adjust the instruction based on the tone
-    let tone_instruction = match tone.as_str() {
-        "Formal" => "Use a professional, formal, and polite tone suitable for business contexts.",
-        "Casual" => "Use a casual, natural, and conversational tone as used in daily life.",
-        "Academic" => "Use an academic, rigorous, and objective tone with appropriate terminology.",
-        "Creative" => {
-            "Use a creative, vivid, and expressive tone with literary devices if appropriate."
+    let (request, auto_target) =
+        translation_request(&client, &settings, text, source_lang, target_lang, tone).await;
+    // Drop what an earlier, failed attempt streamed before the next one
+    let attempted = AtomicBool::new(false);
+    let start_attempt = || {
+        if attempted.swap(true, Ordering::Relaxed) {
+            let reset = TranslationReset { request_id };
+            if let Err(e) = app.emit_to("translator", "translation-reset", reset) {
+                error!("[get_translation_stream] Failed to emit reset: {}", e);
+            }
         }
-        _ => "Use a natural and fluent tone.",
     };
+    let (engine, result) = translate_with_fallback(
+        &app,
+        Some(&in_flight),
+        &cache,
+        &settings,
+        &request,
+        |engine| {
+            start_attempt();
+            let handle = app.clone();
+            let client = client.clone();
+            let settings = settings.clone();
+            let request = request.clone();
+            async move {
+                let on_delta = move |delta: &str| emit_delta(&handle, request_id, delta);
+                engine
+                    .translate_stream(&client, &settings, &request, &on_delta)
+                    .await
+            }
+        },
+        |translation| {
+            start_attempt();
+            emit_delta(&app, request_id, &translation.text);
+        },
+    )
+    .await;
+    let result = finish_translation(&app, &settings, engine.id(), &request, auto_target, result);
+
+    let done = TranslationDone {
+        request_id,
+        translation: result.as_ref().ok().cloned(),
+        error: result.as_ref().err().cloned(),
+    };
+    if let Err(e) = app.emit_to("translator", "translation-done", done) {
+        error!("[get_translation_stream] Failed to emit done: {}", e);
+    }
 
-    let system_prompt = format!(
-        "You are a professional translation engine. Translate the provided text into {target}. {tone_instruction} Requirements: Output ONLY the translated text without explanations, quotes, Markdown, numbering, or extra content. Preserve original line breaks and formatting as much as possible.",
-    );
+    result
+}
 
-    let payload = GlmChatCompletionRequest {
-        model: "glm-4.6".to_string(),
-        messages: vec![
-            GlmMessage {
-                role: "system".to_string(),
-                content: system_prompt,
-            },
-            GlmMessage {
-                role: "user".to_string(),
-                content: text,
-            },
-        ],
-        temperature: 0.2,
-        top_p: 0.9,
-        stream: false,
-        do_sample: false,
-        response_format: GlmResponseFormat {
-            kind: "text".to_string(),
-        },
+/// One engine's entry in a comparison
+#[derive(Clone, Serialize)]
+struct EngineComparison {
+    engine: String,
+    translation: Option<Translation>,
+    error: Option<AppError>,
+    /// Time until the engine answered, including the cache lookup
+    latency_ms: u64,
+}
+
+#[derive(Clone, Serialize)]
+struct ComparisonProgress {
+    request_id: u64,
+    result: EngineComparison,
+}
+
+/// Engines to compare: the requested ids, or every engine whose config is
+/// complete. Requested engines that can't run are reported as errors.
+fn comparison_engines(
+    settings: &AppSettings,
+    ids: Option<Vec<String>>,
+) -> Vec<(String, Result<&'static dyn TranslationEngine, AppError>)> {
+    match ids {
+        Some(ids) => ids
+            .into_iter()
+            .map(|id| {
+                let engine = engines::find_engine(&id)
+                    .ok_or_else(|| AppError::invalid_config("Invalid engine name"))
+                    .and_then(|engine| engine.validate_config(settings).map(|_| engine))
+                    .map_err(|e| e.with_engine(&id));
+                (id, engine)
+            })
+            .collect(),
+        None => engines::all_engines()
+            .filter(|engine| engine.validate_config(settings).is_ok())
+            .map(|engine| (engine.id().to_string(), Ok(engine)))
+            .collect(),
+    }
+}
+
+async fn compare_engine(
+    app: AppHandle,
+    client: reqwest::Client,
+    settings: Arc<AppSettings>,
+    engine: &'static dyn TranslationEngine,
+    request: Arc<TranslationRequest>,
+    auto_target: Option<String>,
+) -> EngineComparison {
+    let started = time::Instant::now();
+    let cache = app.state::<TranslationCache>();
+    let key = CacheKey::new(engine, &settings, &request);
+
+    let result = match cache.get(&app, settings.disk_cache_enabled, &key).await {
+        Some(translation) => Ok(translation),
+        None => {
+            let result = engine.translate(&client, &settings, &request).await;
+            if let Ok(translation) = &result {
+                cache.insert(&app, settings.disk_cache_enabled, key, translation.clone());
+            }
+            result
+        }
     };
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    // Compared results are not recorded in the history; only the one the
+    // user picks is worth keeping
+    let result = result.map(|mut translation| {
+        translation.auto_target_lang = auto_target;
+        translation.glossary_violations =
+            glossary::verify(&settings.glossary, &request, &translation.text);
+        translation
+    });
+    let result = result.map_err(|e| e.with_engine(engine.id()));
+    if let Err(e) = &result {
+        warn!("[compare_translations] '{}' failed: {}", engine.id(), e);
+    }
 
-    let client = reqwest::Client::new();
-    let res = client
-        .post("https://open.bigmodel.cn/api/paas/v4/chat/completions")
-        .header("Authorization", format!("Bearer {}", api_key))
-        .json(&payload)
-        .send()
-        .await
-        .map_err(|e| format!("请求失败: {}", e))?;
+    EngineComparison {
+        engine: engine.id().to_string(),
+        translation: result.as_ref().ok().cloned(),
+        error: result.err(),
+        latency_ms,
+    }
+}
 
-    if !res.status().is_success() {
-        let status = res.status();
-        let body = res.text().await.unwrap_or_default();
-        return Err(format!("接口返回错误 ({}): {}", status, body));
+fn emit_comparison(app: &AppHandle, request_id: u64, result: &EngineComparison) {
+    let progress = ComparisonProgress {
+        request_id,
+        result: result.clone(),
+    };
+    if let Err(e) = app.emit_to("translator", "translation-compare", progress) {
+        error!("[compare_translations] Failed to emit result: {}", e);
     }
+}
 
-    let data: GlmChatCompletionResponse = res
-        .json()
-        .await
-        .map_err(|e| format!("解析响应失败: {}", e))?;
+// Translate with several engines at once. Each result is emitted to the
+// translator window as `translation-compare` when its engine finishes; the
+// command resolves with all of them in completion order.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn compare_translations(
+    app: AppHandle,
+    tasks: State<'_, InFlightTranslations>,
+    request_id: u64,
+    text: String,
+    source_lang: Option<String>,
+    target_lang: String,
+    tone: String,
+    engines: Option<Vec<String>>,
+) -> Result<Vec<EngineComparison>, AppError> {
+    let in_flight = tasks.register(request_id);
+    let settings = Arc::new(read_app_settings(&app).await.unwrap_or_default());
+    let client = http_client(&app, &settings)?;
+    let engines = comparison_engines(&settings, engines);
+    if engines.is_empty() {
+        return Err(AppError::invalid_config("没有已配置的翻译引擎"));
+    }
+
+    info!(
+        "[compare_translations] #{} Comparing {} engine(s). Text length: {}, target: {}",
+        request_id,
+        engines.len(),
+        text.len(),
+        target_lang
+    );
+
+    let (request, auto_target) =
+        translation_request(&client, &settings, text, source_lang, target_lang, tone).await;
+    let request = Arc::new(request);
+
+    let handle = app.clone();
+    let comparison = async move {
+        let mut results = Vec::with_capacity(engines.len());
+        let mut running = tokio::task::JoinSet::new();
+        for (id, engine) in engines {
+            match engine {
+                Ok(engine) => {
+                    running.spawn(compare_engine(
+                        handle.clone(),
+                        client.clone(),
+                        settings.clone(),
+                        engine,
+                        request.clone(),
+                        auto_target.clone(),
+                    ));
+                }
+                Err(e) => {
+                    let result = EngineComparison {
+                        engine: id,
+                        translation: None,
+                        error: Some(e),
+                        latency_ms: 0,
+                    };
+                    emit_comparison(&handle, request_id, &result);
+                    results.push(result);
+                }
+            }
+        }
+
+        // Dropping the set on cancellation aborts the engines still running
+        while let Some(joined) = running.join_next().await {
+            match joined {
+                Ok(result) => {
+                    emit_comparison(&handle, request_id, &result);
+                    results.push(result);
+                }
+                Err(e) => error!("[compare_translations] Engine task failed: {}", e),
+            }
+        }
+        Ok(results)
+    };
 
-    let content = data
-        .choices
-        .first()
-        .map(|c| c.message.content.clone())
-        .unwrap_or_else(|| "翻译失败：未返回内容".to_string());
+    in_flight.run(comparison).await
+}
+
+#[tauri::command]
+async fn set_disk_cache_enabled(app: AppHandle, enabled: bool) -> Result<(), AppError> {
+    let mut settings = read_app_settings(&app).await.unwrap_or_default();
+    settings.disk_cache_enabled = enabled;
+    write_app_settings(&app, &settings).await
+}
+
+#[tauri::command]
+async fn get_translation_cache_info(
+    app: AppHandle,
+    cache: State<'_, TranslationCache>,
+) -> Result<CacheInfo, AppError> {
+    let settings = read_app_settings(&app).await.unwrap_or_default();
+    Ok(cache.info(&app, settings.disk_cache_enabled).await)
+}
+
+#[tauri::command]
+async fn clear_translation_cache(
+    app: AppHandle,
+    cache: State<'_, TranslationCache>,
+) -> Result<(), AppError> {
+    cache.clear(&app).await
+}
 
-    Ok(strip_code_fences(&content))
+fn history_store(app: &AppHandle) -> Result<State<'_, HistoryStore>, AppError> {
+    app.try_state::<HistoryStore>()
+        .ok_or_else(|| AppError::storage("翻译历史不可用"))
+}
+
+#[tauri::command]
+async fn set_history_config(
+    app: AppHandle,
+    enabled: bool,
+    retention_days: Option<u32>,
+) -> Result<(), AppError> {
+    let mut settings = read_app_settings(&app).await.unwrap_or_default();
+    settings.history = HistorySettings {
+        enabled,
+        retention_days: retention_days.filter(|days| *days > 0),
+    };
+    write_app_settings(&app, &settings).await?;
+    prune_history(&app).await;
+    Ok(())
+}
+
+#[tauri::command]
+fn list_history(
+    app: AppHandle,
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> Result<Vec<HistoryEntry>, AppError> {
+    history_store(&app)?.list(limit.unwrap_or(50), offset.unwrap_or(0))
+}
+
+// Ranked full-text search, optionally narrowed by engine, language pair and date
+#[tauri::command]
+fn search_history(
+    app: AppHandle,
+    query: String,
+    filter: Option<HistoryFilter>,
+    limit: Option<u32>,
+) -> Result<Vec<HistorySearchHit>, AppError> {
+    history_store(&app)?.search(&query, &filter.unwrap_or_default(), limit.unwrap_or(50))
+}
+
+#[tauri::command]
+fn delete_history_entry(app: AppHandle, id: i64) -> Result<(), AppError> {
+    history_store(&app)?.delete(id)
+}
+
+#[tauri::command]
+fn clear_history(app: AppHandle) -> Result<(), AppError> {
+    history_store(&app)?.clear()
+}
+
+fn phrasebook_store(app: &AppHandle) -> Result<State<'_, PhrasebookStore>, AppError> {
+    app.try_state::<PhrasebookStore>()
+        .ok_or_else(|| AppError::storage("短语本不可用"))
+}
+
+// Star a translation into the phrasebook
+#[tauri::command]
+fn add_phrase(app: AppHandle, phrase: NewPhrase) -> Result<Phrase, AppError> {
+    phrasebook_store(&app)?.add(phrase)
+}
+
+#[tauri::command]
+fn set_phrase_tags(app: AppHandle, id: i64, tags: Vec<String>) -> Result<Phrase, AppError> {
+    phrasebook_store(&app)?.set_tags(id, tags)
+}
+
+#[tauri::command]
+fn search_phrases(
+    app: AppHandle,
+    query: Option<String>,
+    tag: Option<String>,
+) -> Result<Vec<Phrase>, AppError> {
+    let query = query.as_deref().map(str::trim);
+    phrasebook_store(&app)?.search(query, tag.as_deref())
+}
+
+#[tauri::command]
+fn list_phrase_tags(app: AppHandle) -> Result<Vec<String>, AppError> {
+    phrasebook_store(&app)?.tags()
+}
+
+#[tauri::command]
+fn delete_phrase(app: AppHandle, id: i64) -> Result<(), AppError> {
+    phrasebook_store(&app)?.delete(id)
+}
+
+// Paste a saved phrase into the previously focused app, without translating again
+#[tauri::command]
+async fn paste_phrase(app: AppHandle, id: i64) -> Result<(), AppError> {
+    let phrase = phrasebook_store(&app)?
+        .get(id)?
+        .ok_or_else(|| AppError::new(ErrorCode::NotFound, "短语不存在"))?;
+    paste_translation(app, phrase.translated_text).await
+}
+
+fn clipboard_error(e: arboard::Error) -> AppError {
+    AppError::clipboard(e.to_string())
+}
+
+fn window_error(e: tauri::Error) -> AppError {
+    AppError::window(e.to_string())
 }
 
 // Get selected text from clipboard
 #[tauri::command]
-async fn get_selected_text() -> Result<String, String> {
+async fn get_selected_text() -> Result<String, AppError> {
     info!("[get_selected_text] Called");
 
     // Read from clipboard directly - user should copy text first (Cmd+C)
     debug!("[get_selected_text] Reading from clipboard");
     let mut clipboard = Clipboard::new().map_err(|e| {
         error!("[get_selected_text] Failed to create clipboard: {}", e);
-        format!("Failed to access clipboard: {}", e)
+        AppError::clipboard(format!("Failed to access clipboard: {}", e))
     })?;
 
     let text = clipboard.get_text().unwrap_or_default();
 
     if text.trim().is_empty() {
         warn!("[get_selected_text] Clipboard is empty");
-        return Err(
-            "No text in clipboard. Please copy text first (Cmd+C), then use Alt+T.".to_string(),
-        );
+        return Err(AppError::clipboard(
+            "No text in clipboard. Please copy text first (Cmd+C), then use Alt+T.",
+        ));
     }
 
     info!(
@@ -451,26 +1354,26 @@ async fn get_selected_text() -> Result<String, String> {
 
 // Copy text to clipboard
 #[tauri::command]
-async fn copy_to_clipboard(text: String) -> Result<(), String> {
-    let mut clipboard = Clipboard::new().map_err(|e| e.to_string())?;
-    clipboard.set_text(text).map_err(|e| e.to_string())
+async fn copy_to_clipboard(text: String) -> Result<(), AppError> {
+    let mut clipboard = Clipboard::new().map_err(clipboard_error)?;
+    clipboard.set_text(text).map_err(clipboard_error)
 }
 
 // Show translator window at cursor position
 #[tauri::command]
-async fn show_translator_window(app: AppHandle) -> Result<(), String> {
+async fn show_translator_window(app: AppHandle) -> Result<(), AppError> {
     let window = app
         .get_webview_window("translator")
-        .ok_or("Translator window not found")?;
+        .ok_or_else(|| AppError::window("Translator window not found"))?;
 
     // Calculate center position
     let mut final_x = 0;
     let mut final_y = 0;
 
-    if let Some(monitor) = window.current_monitor().map_err(|e| e.to_string())? {
+    if let Some(monitor) = window.current_monitor().map_err(window_error)? {
         let screen_size = monitor.size();
         let screen_pos = monitor.position();
-        let window_size = window.outer_size().map_err(|e| e.to_string())?;
+        let window_size = window.outer_size().map_err(window_error)?;
 
         final_x = screen_pos.x + (screen_size.width as i32 - window_size.width as i32) / 2;
         final_y = screen_pos.y + (screen_size.height as i32 - window_size.height as i32) / 2;
@@ -539,10 +1442,10 @@ async fn show_translator_window(app: AppHandle) -> Result<(), String> {
             x: final_x,
             y: final_y,
         }))
-        .map_err(|e| e.to_string())?;
+        .map_err(window_error)?;
 
-    window.show().map_err(|e| e.to_string())?;
-    window.set_focus().map_err(|e| e.to_string())?;
+    window.show().map_err(window_error)?;
+    window.set_focus().map_err(window_error)?;
 
     Ok(())
 }
@@ -571,10 +1474,10 @@ fn hide_application(_app: &AppHandle) {
 
 // Paste translation to the previous active window
 #[tauri::command]
-async fn paste_translation(app: AppHandle, text: String) -> Result<(), String> {
+async fn paste_translation(app: AppHandle, text: String) -> Result<(), AppError> {
     // 1. Copy text to clipboard
-    let mut clipboard = Clipboard::new().map_err(|e| e.to_string())?;
-    clipboard.set_text(text).map_err(|e| e.to_string())?;
+    let mut clipboard = Clipboard::new().map_err(clipboard_error)?;
+    clipboard.set_text(text).map_err(clipboard_error)?;
 
     // 2. Hide translator window
     if let Some(translator_window) = app.get_webview_window("translator") {
@@ -664,12 +1567,12 @@ async fn paste_translation(app: AppHandle, text: String) -> Result<(), String> {
 
 // Hide translator window
 #[tauri::command]
-async fn hide_translator_window(app: AppHandle) -> Result<(), String> {
+async fn hide_translator_window(app: AppHandle) -> Result<(), AppError> {
     let window = app
         .get_webview_window("translator")
-        .ok_or("Translator window not found")?;
+        .ok_or_else(|| AppError::window("Translator window not found"))?;
 
-    window.hide().map_err(|e| e.to_string())?;
+    window.hide().map_err(window_error)?;
     hide_application(&app);
 
     Ok(())
@@ -677,52 +1580,50 @@ async fn hide_translator_window(app: AppHandle) -> Result<(), String> {
 
 // Toggle translator window always-on-top (pin)
 #[tauri::command]
-async fn toggle_translator_pin(app: AppHandle, pinned: bool) -> Result<(), String> {
+async fn toggle_translator_pin(app: AppHandle, pinned: bool) -> Result<(), AppError> {
     let window = app
         .get_webview_window("translator")
-        .ok_or("Translator window not found")?;
+        .ok_or_else(|| AppError::window("Translator window not found"))?;
 
-    window
-        .set_always_on_top(pinned)
-        .map_err(|e| e.to_string())?;
+    window.set_always_on_top(pinned).map_err(window_error)?;
 
     Ok(())
 }
 
 // Show settings window
 #[tauri::command]
-async fn show_settings_window(app: AppHandle) -> Result<(), String> {
+async fn show_settings_window(app: AppHandle) -> Result<(), AppError> {
     let window = app
         .get_webview_window("settings")
-        .ok_or("Settings window not found")?;
+        .ok_or_else(|| AppError::window("Settings window not found"))?;
 
-    window.show().map_err(|e| e.to_string())?;
-    window.set_focus().map_err(|e| e.to_string())?;
+    window.show().map_err(window_error)?;
+    window.set_focus().map_err(window_error)?;
 
     Ok(())
 }
 
 // Hide settings window
 #[tauri::command]
-async fn hide_settings_window(app: AppHandle) -> Result<(), String> {
+async fn hide_settings_window(app: AppHandle) -> Result<(), AppError> {
     let window = app
         .get_webview_window("settings")
-        .ok_or("Settings window not found")?;
+        .ok_or_else(|| AppError::window("Settings window not found"))?;
 
-    window.hide().map_err(|e| e.to_string())?;
+    window.hide().map_err(window_error)?;
 
     Ok(())
 }
 
 // Show main window
 #[tauri::command]
-async fn show_main_window(app: AppHandle) -> Result<(), String> {
+async fn show_main_window(app: AppHandle) -> Result<(), AppError> {
     let window = app
         .get_webview_window("main")
-        .ok_or("Main window not found")?;
+        .ok_or_else(|| AppError::window("Main window not found"))?;
 
-    window.show().map_err(|e| e.to_string())?;
-    window.set_focus().map_err(|e| e.to_string())?;
+    window.show().map_err(window_error)?;
+    window.set_focus().map_err(window_error)?;
 
     Ok(())
 }
@@ -746,7 +1647,7 @@ async fn update_global_shortcut(
     app: AppHandle,
     old_shortcut: String,
     new_shortcut: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     info!(
         "[Global Shortcut] Updating from '{}' to '{}'",
         old_shortcut, new_shortcut
@@ -787,15 +1688,18 @@ async fn update_global_shortcut(
         ) {
             Ok(_) => {
                 if let Err(e) = app.global_shortcut().register(new_shortcut.as_str()) {
-                    return Err(format!("Failed to register '{}': {}", new_shortcut, e));
+                    return Err(AppError::invalid_config(format!(
+                        "Failed to register '{}': {}",
+                        new_shortcut, e
+                    )));
                 }
                 info!("[Global Shortcut] Registered '{}'", new_shortcut);
             }
             Err(e) => {
-                return Err(format!(
+                return Err(AppError::invalid_config(format!(
                     "Failed to setup handler for '{}': {}",
                     new_shortcut, e
-                ));
+                )));
             }
         }
     }
@@ -820,6 +1724,9 @@ pub fn run() {
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_macos_permissions::init())
+        .manage(InFlightTranslations::default())
+        .manage(TranslationCache::default())
+        .manage(HttpClient::default())
         .on_window_event(|window, event| {
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
                 if window.label() == "main" {
@@ -832,6 +1739,22 @@ pub fn run() {
             info!("[Setup] Application starting");
             let handle = app.handle().clone();
 
+            // History is optional; keep running if the database can't be opened
+            match HistoryStore::open(&handle) {
+                Ok(store) => {
+                    app.manage(store);
+                    let handle = handle.clone();
+                    tauri::async_runtime::spawn(async move { prune_history(&handle).await });
+                }
+                Err(e) => error!("[Setup] Failed to open history database: {}", e),
+            }
+            match PhrasebookStore::open(&handle) {
+                Ok(store) => {
+                    app.manage(store);
+                }
+                Err(e) => error!("[Setup] Failed to open phrasebook database: {}", e),
+            }
+
             // 为 translator 窗口应用 macOS 原生模糊效果
             #[cfg(target_os = "macos")]
             if let Some(window) = app.get_webview_window("translator") {
@@ -927,8 +1850,46 @@ pub fn run() {
             get_app_settings,
             set_zhipu_api_key,
             set_tencent_config,
+            set_openai_config,
+            set_ollama_config,
+            list_ollama_models,
+            set_deepl_config,
+            list_deepl_glossaries,
+            set_baidu_config,
+            set_youdao_config,
+            set_azure_config,
+            translate_azure_multi,
+            set_anthropic_config,
+            set_gemini_config,
+            set_libretranslate_config,
+            list_libretranslate_languages,
+            set_glossary,
+            set_language_detection_config,
+            detect_language,
             set_active_engine,
+            set_fallback_engines,
+            set_retry_policy,
+            set_network_settings,
+            list_engines,
+            list_languages,
             get_translation,
+            get_translation_stream,
+            compare_translations,
+            cancel_translation,
+            set_disk_cache_enabled,
+            get_translation_cache_info,
+            clear_translation_cache,
+            set_history_config,
+            list_history,
+            search_history,
+            delete_history_entry,
+            clear_history,
+            add_phrase,
+            set_phrase_tags,
+            search_phrases,
+            list_phrase_tags,
+            delete_phrase,
+            paste_phrase,
             get_selected_text,
             copy_to_clipboard,
             show_translator_window,
@@ -953,6 +1914,11 @@ pub fn run() {
                         let _ = window.set_focus();
                     }
                 }
+                tauri::RunEvent::Exit => {
+                    // Write cache entries still waiting for their delayed write
+                    let cache = app.state::<TranslationCache>();
+                    tauri::async_runtime::block_on(cache.flush(app));
+                }
                 _ => {}
             }
         });
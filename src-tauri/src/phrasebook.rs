@@ -0,0 +1,283 @@
+//! Phrasebook of starred translations, tagged for reuse.
+//!
+//! Phrases are stored in `phrasebook.db` in the app data dir, next to the
+//! translation history.
+
+use std::sync::Mutex;
+
+use chrono::Utc;
+use log::info;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::error::{AppError, ErrorCode};
+
+const PHRASEBOOK_DB: &str = "phrasebook.db";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Phrase {
+    pub id: i64,
+    pub source_text: String,
+    pub translated_text: String,
+    pub engine: Option<String>,
+    pub target_lang: String,
+    pub tags: Vec<String>,
+    /// Unix timestamp in seconds
+    pub created_at: i64,
+}
+
+impl Phrase {
+    /// Reads every column except `tags`, which live in their own table
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get(0)?,
+            source_text: row.get(1)?,
+            translated_text: row.get(2)?,
+            engine: row.get(3)?,
+            target_lang: row.get(4)?,
+            tags: Vec::new(),
+            created_at: row.get(5)?,
+        })
+    }
+}
+
+/// A translation to star, as sent by the frontend
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewPhrase {
+    pub source_text: String,
+    pub translated_text: String,
+    pub engine: Option<String>,
+    pub target_lang: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+const SELECT_PHRASE: &str =
+    "SELECT p.id, p.source_text, p.translated_text, p.engine, p.target_lang, p.created_at FROM phrases p";
+
+fn db_error(e: rusqlite::Error) -> AppError {
+    AppError::storage(format!("Phrasebook database error: {}", e))
+}
+
+/// Trim tags and drop blanks and case-insensitive duplicates
+fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim();
+        if !tag.is_empty() && !normalized.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            normalized.push(tag.to_string());
+        }
+    }
+    normalized
+}
+
+pub struct PhrasebookStore(Mutex<Connection>);
+
+impl PhrasebookStore {
+    pub fn open(app: &AppHandle) -> Result<Self, AppError> {
+        let dir = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| AppError::storage(format!("Failed to resolve app data dir: {}", e)))?;
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| AppError::storage(format!("Failed to create data dir: {}", e)))?;
+
+        let path = dir.join(PHRASEBOOK_DB);
+        info!("[phrasebook] Opening {:?}", path);
+        Self::init(Connection::open(&path).map_err(db_error)?)
+    }
+
+    /// Create the schema on `conn` if it isn't there yet
+    fn init(conn: Connection) -> Result<Self, AppError> {
+        conn.execute_batch(
+            "PRAGMA foreign_keys = ON;
+            CREATE TABLE IF NOT EXISTS phrases (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                source_text TEXT NOT NULL,
+                translated_text TEXT NOT NULL,
+                engine TEXT,
+                target_lang TEXT NOT NULL,
+                created_at INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS phrase_tags (
+                phrase_id INTEGER NOT NULL REFERENCES phrases (id) ON DELETE CASCADE,
+                tag TEXT NOT NULL COLLATE NOCASE,
+                PRIMARY KEY (phrase_id, tag)
+            );
+            CREATE INDEX IF NOT EXISTS phrase_tags_tag ON phrase_tags (tag);",
+        )
+        .map_err(db_error)?;
+
+        Ok(Self(Mutex::new(conn)))
+    }
+
+    pub fn add(&self, phrase: NewPhrase) -> Result<Phrase, AppError> {
+        let mut conn = self.0.lock().unwrap();
+        let tx = conn.transaction().map_err(db_error)?;
+        tx.execute(
+            "INSERT INTO phrases (source_text, translated_text, engine, target_lang, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                phrase.source_text,
+                phrase.translated_text,
+                phrase.engine,
+                phrase.target_lang,
+                Utc::now().timestamp()
+            ],
+        )
+        .map_err(db_error)?;
+        let id = tx.last_insert_rowid();
+        set_tags(&tx, id, normalize_tags(phrase.tags)).map_err(db_error)?;
+        tx.commit().map_err(db_error)?;
+
+        get(&conn, id)?.ok_or_else(|| AppError::new(ErrorCode::NotFound, "短语不存在"))
+    }
+
+    pub fn get(&self, id: i64) -> Result<Option<Phrase>, AppError> {
+        get(&self.0.lock().unwrap(), id)
+    }
+
+    /// Replace the tags of a phrase
+    pub fn set_tags(&self, id: i64, tags: Vec<String>) -> Result<Phrase, AppError> {
+        let mut conn = self.0.lock().unwrap();
+        let tx = conn.transaction().map_err(db_error)?;
+        // Otherwise inserting the tags would fail on the foreign key
+        let exists = tx
+            .query_row("SELECT 1 FROM phrases WHERE id = ?1", params![id], |_| {
+                Ok(())
+            })
+            .optional()
+            .map_err(db_error)?
+            .is_some();
+        if !exists {
+            return Err(AppError::new(ErrorCode::NotFound, "短语不存在"));
+        }
+        tx.execute("DELETE FROM phrase_tags WHERE phrase_id = ?1", params![id])
+            .map_err(db_error)?;
+        set_tags(&tx, id, normalize_tags(tags)).map_err(db_error)?;
+        tx.commit().map_err(db_error)?;
+
+        get(&conn, id)?.ok_or_else(|| AppError::new(ErrorCode::NotFound, "短语不存在"))
+    }
+
+    /// Phrases containing `query` in either text and carrying `tag`, newest first
+    pub fn search(&self, query: Option<&str>, tag: Option<&str>) -> Result<Vec<Phrase>, AppError> {
+        let pattern = query.filter(|q| !q.is_empty()).map(|q| {
+            let escaped = q
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_");
+            format!("%{}%", escaped)
+        });
+
+        let conn = self.0.lock().unwrap();
+        let mut stmt = conn
+            .prepare(&format!(
+                "{} WHERE (?1 IS NULL
+                    OR p.source_text LIKE ?1 ESCAPE '\\' OR p.translated_text LIKE ?1 ESCAPE '\\')
+                 AND (?2 IS NULL
+                    OR EXISTS (SELECT 1 FROM phrase_tags t WHERE t.phrase_id = p.id AND t.tag = ?2))
+                 ORDER BY p.created_at DESC, p.id DESC",
+                SELECT_PHRASE
+            ))
+            .map_err(db_error)?;
+        let mut phrases = stmt
+            .query_map(params![pattern, tag], Phrase::from_row)
+            .map_err(db_error)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(db_error)?;
+
+        for phrase in &mut phrases {
+            phrase.tags = tags_of(&conn, phrase.id).map_err(db_error)?;
+        }
+        Ok(phrases)
+    }
+
+    /// Every tag in use, alphabetically
+    pub fn tags(&self) -> Result<Vec<String>, AppError> {
+        let conn = self.0.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT DISTINCT tag FROM phrase_tags ORDER BY tag")
+            .map_err(db_error)?;
+        let rows = stmt.query_map([], |row| row.get(0)).map_err(db_error)?;
+        rows.collect::<Result<_, _>>().map_err(db_error)
+    }
+
+    pub fn delete(&self, id: i64) -> Result<(), AppError> {
+        let conn = self.0.lock().unwrap();
+        conn.execute("DELETE FROM phrases WHERE id = ?1", params![id])
+            .map_err(db_error)?;
+        Ok(())
+    }
+}
+
+fn get(conn: &Connection, id: i64) -> Result<Option<Phrase>, AppError> {
+    let phrase = conn
+        .query_row(
+            &format!("{} WHERE p.id = ?1", SELECT_PHRASE),
+            params![id],
+            Phrase::from_row,
+        )
+        .optional()
+        .map_err(db_error)?;
+
+    match phrase {
+        Some(mut phrase) => {
+            phrase.tags = tags_of(conn, id).map_err(db_error)?;
+            Ok(Some(phrase))
+        }
+        None => Ok(None),
+    }
+}
+
+fn tags_of(conn: &Connection, id: i64) -> rusqlite::Result<Vec<String>> {
+    let mut stmt =
+        conn.prepare_cached("SELECT tag FROM phrase_tags WHERE phrase_id = ?1 ORDER BY tag")?;
+    let rows = stmt.query_map(params![id], |row| row.get(0))?;
+    rows.collect()
+}
+
+fn set_tags(conn: &Connection, id: i64, tags: Vec<String>) -> rusqlite::Result<()> {
+    for tag in tags {
+        conn.execute(
+            "INSERT INTO phrase_tags (phrase_id, tag) VALUES (?1, ?2)",
+            params![id, tag],
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store() -> PhrasebookStore {
+        PhrasebookStore::init(Connection::open_in_memory().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn set_tags_replaces_tags() {
+        let store = store();
+        let phrase = store
+            .add(NewPhrase {
+                source_text: "hello".to_string(),
+                translated_text: "你好".to_string(),
+                engine: None,
+                target_lang: "zh".to_string(),
+                tags: vec!["greeting".to_string()],
+            })
+            .unwrap();
+
+        let phrase = store
+            .set_tags(phrase.id, vec!["common".to_string()])
+            .unwrap();
+        assert_eq!(phrase.tags, ["common"]);
+    }
+
+    #[test]
+    fn set_tags_on_missing_phrase_is_not_found() {
+        let error = store().set_tags(42, vec!["tag".to_string()]).unwrap_err();
+        assert_eq!(error.code, ErrorCode::NotFound);
+    }
+}
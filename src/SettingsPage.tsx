@@ -3,21 +3,12 @@ import { invoke } from "@tauri-apps/api/core";
 import { getCurrentWindow } from "@tauri-apps/api/window";
 import { useTranslation } from "react-i18next";
 import { Check, Eye, EyeOff, KeyRound, X } from "lucide-react";
+import { errorMessage } from "./lib/errors";
 
 type AppSettings = {
   zhipu_api_key?: string | null;
 };
 
-function normalizeError(error: unknown): string {
-  if (error instanceof Error) return error.message;
-  if (typeof error === "string") return error;
-  try {
-    return JSON.stringify(error);
-  } catch {
-    return String(error);
-  }
-}
-
 export default function SettingsPage() {
   const { t } = useTranslation();
   const [apiKey, setApiKey] = useState("");
@@ -34,7 +25,7 @@ export default function SettingsPage() {
         const settings = await invoke<AppSettings>("get_app_settings");
         setApiKey(settings?.zhipu_api_key ?? "");
       } catch (e) {
-        setError(normalizeError(e));
+        setError(errorMessage(e, t));
       } finally {
         setLoading(false);
       }
@@ -56,7 +47,7 @@ export default function SettingsPage() {
       setSaved(true);
       setTimeout(() => setSaved(false), 2000);
     } catch (e) {
-      setError(normalizeError(e));
+      setError(errorMessage(e, t));
     } finally {
       setSaving(false);
     }
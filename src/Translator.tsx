@@ -14,6 +14,9 @@ function Translator() {
     inputText,
     setInputText,
     translatedText,
+    dictionary,
+    glossaryViolations,
+    translationError,
     isTranslating,
     sourceLang,
     setSourceLang,
@@ -22,7 +25,9 @@ function Translator() {
     currentTone,
     setCurrentTone,
     activeEngine,
+    languages,
     updateSettings,
+    retryTranslation,
     toggleLanguages,
     clearContent,
   } = useTranslatorState();
@@ -55,6 +60,7 @@ function Translator() {
         )}
       >
         <TranslatorHeader
+          languages={languages}
           sourceLang={sourceLang}
           setSourceLang={setSourceLang}
           targetLang={targetLang}
@@ -79,7 +85,12 @@ function Translator() {
 
           <TranslatorOutput
             translatedText={translatedText}
+            dictionary={dictionary}
+            glossaryViolations={glossaryViolations}
+            error={translationError}
             isTranslating={isTranslating}
+            onRetry={retryTranslation}
+            onOpenSettings={openSettings}
           />
         </div>
       </div>
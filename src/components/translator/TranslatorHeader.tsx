@@ -20,9 +20,10 @@ import {
   TooltipProvider, 
   TooltipTrigger 
 } from "../ui/tooltip";
-import { Language, Tone, LANGUAGES, TONES } from "../../hooks/useTranslatorState";
+import { Language, Tone, AUTO_LANGUAGE, TONES } from "../../hooks/useTranslatorState";
 
 interface TranslatorHeaderProps {
+  languages: Language[];
   sourceLang: Language;
   setSourceLang: (lang: Language) => void;
   targetLang: Language;
@@ -38,6 +39,7 @@ interface TranslatorHeaderProps {
 }
 
 export const TranslatorHeader: React.FC<TranslatorHeaderProps> = ({
+  languages,
   sourceLang,
   setSourceLang,
   targetLang,
@@ -66,8 +68,8 @@ export const TranslatorHeader: React.FC<TranslatorHeaderProps> = ({
               <span className="uppercase">{sourceLang.code}</span>
             </Button>
           </DropdownMenuTrigger>
-          <DropdownMenuContent align="start" className="min-w-[120px]">
-            {LANGUAGES.map((lang) => (
+          <DropdownMenuContent align="start" className="min-w-[120px] max-h-72 overflow-y-auto">
+            {[AUTO_LANGUAGE, ...languages].map((lang) => (
               <DropdownMenuItem 
                 key={lang.code} 
                 onClick={() => setSourceLang(lang)}
@@ -85,6 +87,7 @@ export const TranslatorHeader: React.FC<TranslatorHeaderProps> = ({
           size="icon" 
           className="h-6 w-6 rounded-full hover:bg-accent"
           onClick={toggleLanguages}
+          disabled={sourceLang.code === AUTO_LANGUAGE.code}
         >
           <ArrowRightLeft size={12} className="opacity-60" />
         </Button>
@@ -95,8 +98,8 @@ export const TranslatorHeader: React.FC<TranslatorHeaderProps> = ({
               <span className="uppercase">{targetLang.code}</span>
             </Button>
           </DropdownMenuTrigger>
-          <DropdownMenuContent align="start" className="min-w-[120px]">
-            {LANGUAGES.map((lang) => (
+          <DropdownMenuContent align="start" className="min-w-[120px] max-h-72 overflow-y-auto">
+            {languages.map((lang) => (
               <DropdownMenuItem 
                 key={lang.code} 
                 onClick={() => setTargetLang(lang)}
@@ -1,6 +1,9 @@
+import { useTranslation } from "react-i18next";
 import { Separator } from "../ui/separator";
 import { ScrollArea } from "../ui/scroll-area";
 import { cn } from "../../lib/utils";
+import { type AppError, errorAction, errorMessage } from "../../lib/errors";
+import type { DictionaryEntry, GlossaryViolation } from "../../hooks/useTranslatorState";
 
 const ThinkingIndicator: React.FC = () => (
   <div className="flex items-center space-x-1 py-1">
@@ -16,14 +19,85 @@ const ThinkingIndicator: React.FC = () => (
 
 interface TranslatorOutputProps {
   translatedText: string;
+  dictionary: DictionaryEntry | null;
+  glossaryViolations: GlossaryViolation[];
+  error: AppError | null;
   isTranslating: boolean;
+  onRetry: () => void;
+  onOpenSettings: () => void;
 }
 
+const DictionaryDetails: React.FC<{ entry: DictionaryEntry }> = ({ entry }) => {
+  const phonetics = [
+    entry.us_phonetic && `US /${entry.us_phonetic}/`,
+    entry.uk_phonetic && `UK /${entry.uk_phonetic}/`,
+  ].filter(Boolean);
+  if (phonetics.length === 0 && entry.phonetic) {
+    phonetics.push(`/${entry.phonetic}/`);
+  }
+
+  return (
+    <div className="mt-2 space-y-1 text-xs text-muted-foreground">
+      {phonetics.length > 0 && <div>{phonetics.join("  ")}</div>}
+      {entry.explains.map((explain) => (
+        <div key={explain}>{explain}</div>
+      ))}
+    </div>
+  );
+};
+
+const GlossaryWarnings: React.FC<{ violations: GlossaryViolation[] }> = ({ violations }) => {
+  const { t } = useTranslation();
+
+  return (
+    <div className="mt-2 space-y-1 text-xs text-amber-600 dark:text-amber-400">
+      {violations.map((violation) => (
+        <div key={violation.source_term}>
+          {t("translator.glossaryViolation", {
+            source: violation.source_term,
+            expected: violation.expected,
+          })}
+        </div>
+      ))}
+    </div>
+  );
+};
+
+const TranslationError: React.FC<{
+  error: AppError;
+  onRetry: () => void;
+  onOpenSettings: () => void;
+}> = ({ error, onRetry, onOpenSettings }) => {
+  const { t } = useTranslation();
+  const action = errorAction(error);
+
+  return (
+    <div className="space-y-2">
+      <div className="text-destructive" title={error.details}>
+        {errorMessage(error, t)}
+      </div>
+      {action && (
+        <button
+          onClick={action === "retry" ? onRetry : onOpenSettings}
+          className="text-xs text-primary hover:underline"
+        >
+          {action === "retry" ? t("errors.retry") : t("errors.openSettings")}
+        </button>
+      )}
+    </div>
+  );
+};
+
 export const TranslatorOutput: React.FC<TranslatorOutputProps> = ({
   translatedText,
+  dictionary,
+  glossaryViolations,
+  error,
   isTranslating,
+  onRetry,
+  onOpenSettings,
 }) => {
-  if (!isTranslating && !translatedText) return null;
+  if (!isTranslating && !translatedText && !error) return null;
 
   return (
     <div className="flex flex-col flex-1 min-h-0 animate-in fade-in slide-in-from-top-2 duration-300">
@@ -40,8 +114,16 @@ export const TranslatorOutput: React.FC<TranslatorOutputProps> = ({
           <div className="text-sm font-medium leading-relaxed text-foreground whitespace-pre-wrap py-1 pr-3 pb-8">
             {isTranslating && !translatedText ? (
               <ThinkingIndicator />
+            ) : error ? (
+              <TranslationError error={error} onRetry={onRetry} onOpenSettings={onOpenSettings} />
             ) : (
-              translatedText
+              <>
+                {translatedText}
+                {dictionary && <DictionaryDetails entry={dictionary} />}
+                {!isTranslating && glossaryViolations.length > 0 && (
+                  <GlossaryWarnings violations={glossaryViolations} />
+                )}
+              </>
             )}
           </div>
         </ScrollArea>
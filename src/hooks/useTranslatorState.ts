@@ -1,13 +1,17 @@
 import { useState, useRef, useCallback, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { type AppError, toAppError } from "../lib/errors";
 
 export type Tone = "Formal" | "Casual" | "Academic" | "Creative";
 
 export interface Language {
     code: string;
     name: string;
+    native_name?: string;
 }
 
+// Used until the backend reports the active engine's languages
 export const LANGUAGES: Language[] = [
     { code: "zh", name: "Chinese" },
     { code: "en", name: "English" },
@@ -15,8 +19,42 @@ export const LANGUAGES: Language[] = [
     { code: "ko", name: "Korean" },
 ];
 
+// Source-only option: the engine detects the language itself
+export const AUTO_LANGUAGE: Language = { code: "auto", name: "Auto Detect" };
+
 export const TONES: Tone[] = ["Formal", "Casual", "Academic", "Creative"];
 
+export interface DictionaryEntry {
+    phonetic?: string | null;
+    us_phonetic?: string | null;
+    uk_phonetic?: string | null;
+    explains: string[];
+}
+
+export interface GlossaryViolation {
+    source_term: string;
+    expected: string;
+}
+
+export interface TranslationResult {
+    text: string;
+    // Engine that produced the text, a fallback engine if the active one failed
+    engine?: string;
+    dictionary?: DictionaryEntry;
+    glossary_violations?: GlossaryViolation[];
+    // Set when auto direction translated into the secondary language instead
+    auto_target_lang?: string;
+}
+
+interface TranslationDelta {
+    request_id: number;
+    delta: string;
+}
+
+interface TranslationReset {
+    request_id: number;
+}
+
 type AppSettings = {
     active_engine?: string;
 };
@@ -24,14 +62,33 @@ type AppSettings = {
 export const useTranslatorState = () => {
     const [inputText, setInputText] = useState("");
     const [translatedText, setTranslatedText] = useState("");
+    const [dictionary, setDictionary] = useState<DictionaryEntry | null>(null);
+    const [glossaryViolations, setGlossaryViolations] = useState<GlossaryViolation[]>([]);
+    const [translationError, setTranslationError] = useState<AppError | null>(null);
     const [isTranslating, setIsTranslating] = useState(false);
-    const [sourceLang, setSourceLang] = useState<Language>(LANGUAGES[0]);
+    const [sourceLang, setSourceLang] = useState<Language>(AUTO_LANGUAGE);
     const [targetLang, setTargetLang] = useState<Language>(LANGUAGES[1]);
     const [currentTone, setCurrentTone] = useState<Tone>("Casual");
     const [activeEngine, setActiveEngine] = useState("zhipu");
+    const [languages, setLanguages] = useState<Language[]>(LANGUAGES);
 
     const debounceTimer = useRef<ReturnType<typeof setTimeout> | null>(null);
     const translateRequestId = useRef(0);
+    // Request whose first streamed delta has already replaced the previous output
+    const streamedRequestId = useRef(0);
+    // Request still running in the backend, if any
+    const inFlightRequestId = useRef<number | null>(null);
+
+    const cancelInFlight = useCallback(() => {
+        const requestId = inFlightRequestId.current;
+        if (requestId === null) return;
+        inFlightRequestId.current = null;
+        // Mark the cancelled request stale so its error isn't shown
+        if (translateRequestId.current === requestId) translateRequestId.current++;
+        invoke("cancel_translation", { requestId }).catch((error) => {
+            console.error("Failed to cancel translation:", error);
+        });
+    }, []);
 
     const updateSettings = useCallback(async () => {
         try {
@@ -46,44 +103,114 @@ export const useTranslatorState = () => {
         updateSettings();
     }, [updateSettings]);
 
+    useEffect(() => {
+        invoke<Language[]>("list_languages", { engine: activeEngine })
+            .then((supported) => {
+                if (supported.length === 0) return;
+                setLanguages(supported);
+                // Keep the selection only if the new engine supports it
+                const isSupported = (lang: Language) =>
+                    supported.some((l) => l.code.toLowerCase() === lang.code.toLowerCase());
+                setSourceLang((lang) =>
+                    lang.code === AUTO_LANGUAGE.code || isSupported(lang) ? lang : supported[0]
+                );
+                setTargetLang((lang) =>
+                    isSupported(lang) ? lang : supported.find((l) => l.code !== "zh") ?? supported[0]
+                );
+            })
+            .catch((error) => {
+                console.error("Failed to fetch languages:", error);
+            });
+    }, [activeEngine]);
+
+    useEffect(() => {
+        const unlistenDelta = listen<TranslationDelta>("translation-delta", (event) => {
+            const { request_id, delta } = event.payload;
+            if (request_id !== translateRequestId.current) return;
+
+            if (streamedRequestId.current !== request_id) {
+                streamedRequestId.current = request_id;
+                setTranslatedText(delta);
+                setDictionary(null);
+                setGlossaryViolations([]);
+            } else {
+                setTranslatedText((prev) => prev + delta);
+            }
+        });
+        // A failed attempt is being retried on the next fallback engine
+        const unlistenReset = listen<TranslationReset>("translation-reset", (event) => {
+            if (event.payload.request_id !== translateRequestId.current) return;
+
+            streamedRequestId.current = 0;
+            setTranslatedText("");
+        });
+
+        return () => {
+            unlistenDelta.then((fn) => fn());
+            unlistenReset.then((fn) => fn());
+        };
+    }, []);
+
     const handleTranslate = useCallback(
         async (text: string) => {
+            cancelInFlight();
             const requestId = ++translateRequestId.current;
 
             if (!text.trim()) {
                 setTranslatedText("");
+                setDictionary(null);
+                setGlossaryViolations([]);
+                setTranslationError(null);
                 setIsTranslating(false);
                 return;
             }
 
             setIsTranslating(true);
+            setTranslationError(null);
+            inFlightRequestId.current = requestId;
 
             try {
-                const translation = await invoke<string>("get_translation", {
+                const translation = await invoke<TranslationResult>("get_translation_stream", {
+                    requestId,
                     text: text,
+                    sourceLang: sourceLang.code,
                     targetLang: targetLang.code.toUpperCase(),
                     tone: currentTone,
                 });
 
                 if (requestId === translateRequestId.current) {
-                    setTranslatedText(translation);
+                    setTranslatedText(translation.text);
+                    setDictionary(translation.dictionary ?? null);
+                    setGlossaryViolations(translation.glossary_violations ?? []);
                 }
             } catch (error) {
                 if (requestId === translateRequestId.current) {
-                    setTranslatedText(`Error: ${error}`);
+                    setTranslatedText("");
+                    setTranslationError(toAppError(error));
+                    setDictionary(null);
+                    setGlossaryViolations([]);
                 }
             } finally {
+                if (inFlightRequestId.current === requestId) {
+                    inFlightRequestId.current = null;
+                }
                 if (requestId === translateRequestId.current) {
                     setIsTranslating(false);
                 }
             }
         },
-        [targetLang, currentTone]
+        [sourceLang, targetLang, currentTone, cancelInFlight]
     );
 
     useEffect(() => {
+        // Whatever is running was for the previous input
+        cancelInFlight();
+
         if (!inputText.trim()) {
             setTranslatedText("");
+            setDictionary(null);
+            setGlossaryViolations([]);
+            setTranslationError(null);
             setIsTranslating(false);
             return;
         }
@@ -97,9 +224,15 @@ export const useTranslatorState = () => {
         return () => {
             if (debounceTimer.current) clearTimeout(debounceTimer.current);
         };
-    }, [inputText, handleTranslate]);
+    }, [inputText, handleTranslate, cancelInFlight]);
+
+    const retryTranslation = useCallback(() => {
+        handleTranslate(inputText);
+    }, [handleTranslate, inputText]);
 
     const toggleLanguages = () => {
+        // "auto" can't become the target, so there is nothing to swap
+        if (sourceLang.code === AUTO_LANGUAGE.code) return;
         setSourceLang(targetLang);
         setTargetLang(sourceLang);
         setInputText(translatedText);
@@ -107,16 +240,23 @@ export const useTranslatorState = () => {
     };
 
     const clearContent = useCallback(() => {
+        cancelInFlight();
         setInputText("");
         setTranslatedText("");
+        setDictionary(null);
+        setGlossaryViolations([]);
+        setTranslationError(null);
         setIsTranslating(false);
-    }, []);
+    }, [cancelInFlight]);
 
     return {
         inputText,
         setInputText,
         translatedText,
         setTranslatedText,
+        dictionary,
+        glossaryViolations,
+        translationError,
         isTranslating,
         sourceLang,
         setSourceLang,
@@ -125,7 +265,9 @@ export const useTranslatorState = () => {
         currentTone,
         setCurrentTone,
         activeEngine,
+        languages,
         updateSettings,
+        retryTranslation,
         toggleLanguages,
         clearContent,
     };
@@ -24,6 +24,7 @@ i18n
                     translator: {
                         placeholder: 'Start typing here...',
                         translating: 'Translating',
+                        glossaryViolation: 'Glossary term not applied: {{source}} → {{expected}}',
                     },
                     dashboard: {
                         title: 'TypeTrans - Write & Translate',
@@ -153,6 +154,28 @@ i18n
                             system: 'System',
                         },
                     },
+                    errors: {
+                        network: 'Network error, check your connection or proxy.',
+                        connection_lost: 'The connection dropped before the engine answered.',
+                        timeout: 'The engine took too long to respond.',
+                        auth: 'Authentication failed, check the API key in settings.',
+                        quota: 'Quota exhausted, check your plan or billing.',
+                        rate_limited: 'Too many requests, try again in a moment.',
+                        invalid_config: 'Configuration is missing or invalid.',
+                        engine_rejected: 'The engine rejected this request.',
+                        content_blocked: 'The engine refused this content under its safety policy.',
+                        engine_unavailable: 'The engine is temporarily unavailable.',
+                        invalid_response: 'The engine returned an unexpected response.',
+                        unsupported_input: 'The text could not be processed, e.g. its language is not recognized.',
+                        cancelled: 'Translation cancelled.',
+                        clipboard: 'Could not access the clipboard.',
+                        window: 'Window operation failed.',
+                        storage: 'Could not read or write local data.',
+                        not_found: 'The item no longer exists.',
+                        internal: 'Something went wrong.',
+                        retry: 'Retry',
+                        openSettings: 'Open Settings',
+                    },
                 },
             },
             zh: {
@@ -167,6 +190,7 @@ i18n
                     translator: {
                         placeholder: '在此开始输入...',
                         translating: '翻译中',
+                        glossaryViolation: '术语未按词汇表翻译：{{source}} → {{expected}}',
                     },
                     dashboard: {
                         title: 'TypeTrans - 边写边译',
@@ -267,6 +291,28 @@ i18n
                             system: '系统',
                         },
                     },
+                    errors: {
+                        network: '网络错误，请检查网络连接或代理设置',
+                        connection_lost: '连接在翻译引擎响应前中断',
+                        timeout: '翻译引擎响应超时',
+                        auth: '认证失败，请在设置中检查 API Key',
+                        quota: '额度已用尽，请检查套餐或账单',
+                        rate_limited: '请求过于频繁，请稍后重试',
+                        invalid_config: '配置缺失或无效',
+                        engine_rejected: '翻译引擎拒绝了该请求',
+                        content_blocked: '翻译引擎因安全策略拒绝了该内容',
+                        engine_unavailable: '翻译引擎暂时不可用',
+                        invalid_response: '翻译引擎返回了无法识别的结果',
+                        unsupported_input: '无法处理该文本，例如无法识别其语言',
+                        cancelled: '翻译已取消',
+                        clipboard: '无法访问剪贴板',
+                        window: '窗口操作失败',
+                        storage: '读写本地数据失败',
+                        not_found: '该条目已不存在',
+                        internal: '出现未知错误',
+                        retry: '重试',
+                        openSettings: '打开设置',
+                    },
                 },
             },
         },
@@ -0,0 +1,74 @@
+import type { TFunction } from "i18next";
+
+// Mirrors `ErrorCode` in src-tauri/src/error.rs
+export type ErrorCode =
+    | "network"
+    | "connection_lost"
+    | "timeout"
+    | "auth"
+    | "quota"
+    | "rate_limited"
+    | "invalid_config"
+    | "engine_rejected"
+    | "content_blocked"
+    | "engine_unavailable"
+    | "invalid_response"
+    | "unsupported_input"
+    | "cancelled"
+    | "clipboard"
+    | "window"
+    | "storage"
+    | "not_found"
+    | "internal";
+
+// Error returned by every backend command
+export interface AppError {
+    code: ErrorCode;
+    // Engine the error came from
+    engine?: string;
+    // Raw backend message, for logs and tooltips
+    details: string;
+}
+
+// What the user can do about an error
+export type ErrorAction = "retry" | "settings";
+
+export function isAppError(error: unknown): error is AppError {
+    return (
+        typeof error === "object" &&
+        error !== null &&
+        typeof (error as AppError).code === "string" &&
+        typeof (error as AppError).details === "string"
+    );
+}
+
+// Wrap errors that don't come from a command, e.g. plugin calls
+export function toAppError(error: unknown): AppError {
+    if (isAppError(error)) return error;
+    if (error instanceof Error) return { code: "internal", details: error.message };
+    return { code: "internal", details: String(error) };
+}
+
+export function errorAction(error: AppError): ErrorAction | null {
+    switch (error.code) {
+        case "network":
+        case "connection_lost":
+        case "timeout":
+        case "rate_limited":
+        case "engine_unavailable":
+            return "retry";
+        case "auth":
+        case "quota":
+        case "invalid_config":
+            return "settings";
+        default:
+            return null;
+    }
+}
+
+// Localized message, prefixed with the engine when there is one
+export function errorMessage(error: unknown, t: TFunction): string {
+    const appError = toAppError(error);
+    const message = t(`errors.${appError.code}`, { defaultValue: appError.details });
+    return appError.engine ? `${appError.engine}: ${message}` : message;
+}
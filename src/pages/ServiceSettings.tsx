@@ -2,6 +2,7 @@ import { useEffect, useMemo, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { useTranslation } from "react-i18next";
 import { cn } from "../lib/utils";
+import { errorMessage } from "../lib/errors";
 import { Check, Eye, EyeOff, KeyRound, Server, Globe } from "lucide-react";
 import { Button } from "../components/ui/button";
 import { Input } from "../components/ui/input";
@@ -17,16 +18,6 @@ type AppSettings = {
   active_engine?: string;
 };
 
-function normalizeError(error: unknown): string {
-  if (error instanceof Error) return error.message;
-  if (typeof error === "string") return error;
-  try {
-    return JSON.stringify(error);
-  } catch {
-    return String(error);
-  }
-}
-
 export default function ServiceSettings() {
   const { t } = useTranslation();
   
@@ -56,7 +47,7 @@ export default function ServiceSettings() {
         setTencentRegion(settings?.tencent_region ?? "ap-guangzhou");
         setActiveEngine(settings?.active_engine ?? "zhipu");
       } catch (e) {
-        setError(normalizeError(e));
+        setError(errorMessage(e, t));
       } finally {
         setLoading(false);
       }
@@ -93,7 +84,7 @@ export default function ServiceSettings() {
       setSaved(true);
       setTimeout(() => setSaved(false), 2000);
     } catch (e) {
-      setError(normalizeError(e));
+      setError(errorMessage(e, t));
     } finally {
       setSaving(false);
     }